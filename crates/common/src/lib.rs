@@ -1,6 +1,10 @@
 pub mod download;
 pub mod migrator;
+pub mod package_json;
 pub mod project_graph;
+pub mod report;
+pub mod workspace_packages;
+pub mod yaml_merge;
 
 use moon_pdk::VirtualPath;
 use std::borrow::Cow;
@@ -1,17 +1,61 @@
+use crate::report::{MigrationReport, MigrationReportKind};
+use crate::yaml_merge::merge_yaml;
+use extism_pdk::*;
 use moon_common::Id;
 use moon_config::{
-    LanguageType, PartialInheritedTasksConfig, PartialProjectConfig, PartialWorkspaceConfig,
-    PlatformType,
+    InheritedTasksConfig, LanguageType, PartialInheritedTasksConfig, PartialProjectConfig,
+    PartialTaskConfig, PartialTaskDependency, PartialToolchainConfig, PartialWorkspaceConfig,
+    PartialWorkspaceProjects, PartialWorkspaceProjectsConfig, PlatformType, ProjectConfig,
+    ToolchainConfig, WorkspaceConfig,
 };
-use moon_pdk::{AnyResult, VirtualPath};
-use rustc_hash::FxHashMap;
-use starbase_utils::yaml;
+use moon_pdk::clap::{self, ValueEnum};
+use moon_pdk::{anyhow, host_log, plugin_err, AnyResult, HostLogInput, HostLogTarget, VirtualPath};
+use moon_target::TargetScope;
+use rustc_hash::{FxHashMap, FxHashSet};
+use schematic::{Config, PartialConfig};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use starbase_utils::yaml::{self, YamlValue};
+use starbase_utils::{fs, glob, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Files (relative to the workspace root) that were touched during a migration,
+/// and are stored in `backup.json` of the backup directory.
+#[derive(Default, Deserialize, Serialize)]
+pub struct MigrationBackup {
+    /// Files that did not exist and were created.
+    pub created: Vec<String>,
+    /// Files that existed and were overwritten. Originals are in the backup directory.
+    pub overwritten: Vec<String>,
+    /// Files that were removed. Originals are in the backup directory.
+    pub removed: Vec<String>,
+}
+
+/// How to handle migrated tasks that collide with tasks of existing moon configs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum TaskConflictStrategy {
+    /// Fail and list every collision, without writing any files.
+    Error,
+    /// Combine inputs, outputs, and deps, with other settings of the migrated task taking precedence.
+    Merge,
+    /// Replace the existing task with the migrated task.
+    #[default]
+    Overwrite,
+    /// Keep the existing task, and drop the migrated task.
+    Skip,
+}
 
 pub struct Migrator {
     pub platform: PlatformType,
     pub project_configs: FxHashMap<VirtualPath, PartialProjectConfig>,
+    pub removed_files: Vec<VirtualPath>,
     pub root: VirtualPath,
     pub tasks_configs: FxHashMap<VirtualPath, PartialInheritedTasksConfig>,
+    pub toolchain_config: Option<PartialToolchainConfig>,
+    pub toolchain_config_path: VirtualPath,
     pub workspace_config: Option<PartialWorkspaceConfig>,
     pub workspace_config_path: VirtualPath,
 }
@@ -21,7 +65,10 @@ impl Migrator {
         Ok(Self {
             platform: PlatformType::Node,
             project_configs: FxHashMap::default(),
+            removed_files: vec![],
             tasks_configs: FxHashMap::default(),
+            toolchain_config: None,
+            toolchain_config_path: workspace_root.join(".moon/toolchain.yml"),
             workspace_config: None,
             workspace_config_path: workspace_root.join(".moon/workspace.yml"),
             root: workspace_root.to_owned(),
@@ -106,6 +153,18 @@ impl Migrator {
         self.load_tasks_config(&platform)
     }
 
+    pub fn load_toolchain_config(&mut self) -> AnyResult<&mut PartialToolchainConfig> {
+        if self.toolchain_config.is_none() {
+            if self.toolchain_config_path.exists() {
+                self.toolchain_config = Some(yaml::read_file(&self.toolchain_config_path)?);
+            } else {
+                self.toolchain_config = Some(PartialToolchainConfig::default());
+            }
+        }
+
+        Ok(self.toolchain_config.as_mut().unwrap())
+    }
+
     pub fn load_workspace_config(&mut self) -> AnyResult<&mut PartialWorkspaceConfig> {
         if self.workspace_config.is_none() {
             if self.workspace_config_path.exists() {
@@ -118,21 +177,716 @@ impl Migrator {
         Ok(self.workspace_config.as_mut().unwrap())
     }
 
-    pub fn save_configs(&self) -> AnyResult<()> {
+    /// Resolve migrated tasks that have the same ID as a task in a moon config that
+    /// already existed, but with different settings, using the provided strategy.
+    pub fn resolve_task_conflicts(&mut self, strategy: TaskConflictStrategy) -> AnyResult<()> {
+        if strategy == TaskConflictStrategy::Overwrite {
+            return Ok(());
+        }
+
+        let mut conflicts = vec![];
+
+        for (config_path, config) in &mut self.tasks_configs {
+            if config_path.exists() {
+                let existing: PartialInheritedTasksConfig = yaml::read_file(config_path)?;
+
+                for task_id in resolve_conflicting_tasks(
+                    existing.tasks.as_ref(),
+                    config.tasks.as_mut(),
+                    strategy,
+                )? {
+                    conflicts.push((config_path.clone(), task_id));
+                }
+            }
+        }
+
+        for (config_path, config) in &mut self.project_configs {
+            if config_path.exists() {
+                let existing: PartialProjectConfig = yaml::read_file(config_path)?;
+
+                for task_id in resolve_conflicting_tasks(
+                    existing.tasks.as_ref(),
+                    config.tasks.as_mut(),
+                    strategy,
+                )? {
+                    conflicts.push((config_path.clone(), task_id));
+                }
+            }
+        }
+
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+
+        let mut conflicts = conflicts
+            .into_iter()
+            .map(|(config_path, task_id)| {
+                format!(
+                    "<file>{}</file>: Task <id>{task_id}</id> already exists.",
+                    self.to_relative_path(&config_path)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        conflicts.sort();
+
+        Err(anyhow!(
+            "Unable to migrate, as migrated tasks conflict with existing tasks. Use <shell>--on-conflict</shell> to overwrite, skip, or merge them:\n  {}",
+            conflicts.join("\n  ")
+        ))
+    }
+
+    pub fn remove_file(&mut self, path: VirtualPath) {
+        if !self.removed_files.contains(&path) {
+            self.removed_files.push(path);
+        }
+    }
+
+    pub fn render_configs(&self) -> AnyResult<Vec<(VirtualPath, String)>> {
+        let mut files = vec![];
+
         if let Some(workspace_config) = &self.workspace_config {
-            yaml::write_file(&self.workspace_config_path, workspace_config)?;
+            files.push((
+                self.workspace_config_path.clone(),
+                format_workspace_config(&self.workspace_config_path, workspace_config)?,
+            ));
         }
 
+        if let Some(toolchain_config) = &self.toolchain_config {
+            files.push((
+                self.toolchain_config_path.clone(),
+                format_config(&self.toolchain_config_path, toolchain_config)?,
+            ));
+        }
+
+        let mut tasks_files = vec![];
+
         for (tasks_config_path, tasks_config) in &self.tasks_configs {
-            yaml::write_file(tasks_config_path, tasks_config)?;
+            tasks_files.push((
+                tasks_config_path.clone(),
+                format_config(tasks_config_path, tasks_config)?,
+            ));
         }
 
+        let mut project_files = vec![];
+
         for (project_config_path, project_config) in &self.project_configs {
-            yaml::write_file(project_config_path, project_config)?;
+            project_files.push((
+                project_config_path.clone(),
+                format_config(project_config_path, project_config)?,
+            ));
+        }
+
+        tasks_files.sort_by(|a, b| a.0.as_path().cmp(b.0.as_path()));
+        project_files.sort_by(|a, b| a.0.as_path().cmp(b.0.as_path()));
+
+        files.extend(tasks_files);
+        files.extend(project_files);
+
+        Ok(files)
+    }
+
+    pub fn diff_configs(&self) -> AnyResult<String> {
+        let files = self.render_configs()?;
+
+        // Fail the same way that writing the configs would
+        self.validate_configs(&files)?;
+
+        let mut output = String::new();
+
+        for (path, content) in files {
+            let rel_path = self.to_relative_path(&path);
+            let (old_header, old_content) = if path.exists() {
+                (rel_path.clone(), fs::read_file(&path)?)
+            } else {
+                ("/dev/null".to_owned(), String::new())
+            };
+
+            if old_content == content {
+                continue;
+            }
+
+            output.push_str(
+                &TextDiff::from_lines(&old_content, &content)
+                    .unified_diff()
+                    .header(&old_header, &rel_path)
+                    .to_string(),
+            );
+        }
+
+        Ok(output)
+    }
+
+    /// Parse and validate the rendered configs the same way moon loads them,
+    /// and ensure that task dependencies resolve to tasks that exist.
+    pub fn validate_configs(&self, files: &[(VirtualPath, String)]) -> AnyResult<()> {
+        let mut errors = vec![];
+
+        for (path, content) in files {
+            let result = if *path == self.workspace_config_path {
+                validate_config::<WorkspaceConfig>(content)
+            } else if *path == self.toolchain_config_path {
+                validate_config::<ToolchainConfig>(content)
+            } else if self.tasks_configs.contains_key(path) {
+                validate_config::<InheritedTasksConfig>(content)
+            } else {
+                validate_config::<ProjectConfig>(content)
+            };
+
+            if let Err(error) = result {
+                errors.push(format!(
+                    "<file>{}</file>: {error}",
+                    self.to_relative_path(path)
+                ));
+            }
+        }
+
+        errors.extend(self.find_unresolved_task_deps()?);
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "Unable to migrate, as the generated configs are invalid:\n  {}",
+            errors.join("\n  ")
+        ))
+    }
+
+    /// Find task dependencies that point to tasks that don't exist, either in the
+    /// project itself, or in another migrated project. Tasks of projects that were
+    /// not migrated are unknown, so dependencies on them are not checked.
+    pub fn find_unresolved_task_deps(&self) -> AnyResult<Vec<String>> {
+        let mut inherited_tasks = FxHashSet::default();
+
+        for tasks_config in self.tasks_configs.values() {
+            inherited_tasks.extend(tasks_config.tasks.iter().flatten().map(|(id, _)| id));
+        }
+
+        // Tasks configs that weren't touched are still inherited
+        let mut existing_tasks_configs = vec![];
+
+        for tasks_config_path in
+            glob::walk_files(self.root.join(".moon"), ["tasks.yml", "tasks/**/*.yml"])?
+        {
+            if !self
+                .tasks_configs
+                .keys()
+                .any(|path| path.as_path() == tasks_config_path)
+            {
+                existing_tasks_configs.push(yaml::read_file::<_, PartialInheritedTasksConfig>(
+                    tasks_config_path,
+                )?);
+            }
+        }
+
+        for tasks_config in &existing_tasks_configs {
+            inherited_tasks.extend(tasks_config.tasks.iter().flatten().map(|(id, _)| id));
+        }
+
+        let project_tasks = self
+            .project_configs
+            .iter()
+            .map(|(config_path, config)| (self.get_project_id(config_path), config))
+            .collect::<FxHashMap<_, _>>();
+
+        let has_task = |project_id: &str, task_id: &Id| {
+            inherited_tasks.contains(task_id)
+                || project_tasks.get(project_id).is_some_and(|config| {
+                    config
+                        .tasks
+                        .as_ref()
+                        .is_some_and(|tasks| tasks.contains_key(task_id))
+                })
+        };
+
+        let mut errors = vec![];
+
+        let mut check_tasks =
+            |config_path: &VirtualPath,
+             project_id: Option<&str>,
+             tasks: Option<&BTreeMap<Id, PartialTaskConfig>>| {
+                for (task_id, task) in tasks.into_iter().flatten() {
+                    for dep in task.deps.iter().flatten() {
+                        let target = match dep {
+                            PartialTaskDependency::Target(target) => Some(target),
+                            PartialTaskDependency::Config(config) => config.target.as_ref(),
+                        };
+
+                        let Some(target) = target else {
+                            continue;
+                        };
+
+                        let dep_project_id = match &target.scope {
+                            TargetScope::OwnSelf => project_id,
+                            TargetScope::Project(id) if project_tasks.contains_key(id.as_str()) => {
+                                Some(id.as_str())
+                            }
+                            _ => None,
+                        };
+
+                        if let Some(dep_project_id) = dep_project_id {
+                            if !has_task(dep_project_id, &target.task_id) {
+                                errors.push(format!(
+                                "<file>{}</file>: Task <id>{task_id}</id> depends on <id>{}</id>, which does not exist.",
+                                self.to_relative_path(config_path),
+                                target.id,
+                            ));
+                            }
+                        }
+                    }
+                }
+            };
+
+        for (config_path, config) in &self.tasks_configs {
+            check_tasks(config_path, None, config.tasks.as_ref());
+        }
+
+        for (config_path, config) in &self.project_configs {
+            let project_id = self.get_project_id(config_path);
+
+            check_tasks(config_path, Some(&project_id), config.tasks.as_ref());
+        }
+
+        errors.sort();
+
+        Ok(errors)
+    }
+
+    /// Determine the ID of a project from its config file, in the same order
+    /// that moon does: the explicit `id` setting, the key in the workspace
+    /// projects map, and lastly the name of the project directory.
+    pub fn get_project_id(&self, project_config_path: &VirtualPath) -> String {
+        let project_root = project_config_path.parent().unwrap();
+
+        if let Some(id) = self
+            .project_configs
+            .get(project_config_path)
+            .and_then(|config| config.id.as_ref())
+        {
+            return id.to_string();
+        }
+
+        if let Some(PartialWorkspaceProjects::Sources(sources)) = self
+            .workspace_config
+            .as_ref()
+            .and_then(|config| config.projects.as_ref())
+        {
+            for (id, source) in sources {
+                if self.root.join(source).as_path() == project_root {
+                    return id.to_string();
+                }
+            }
         }
 
+        fs::file_name(project_root)
+    }
+
+    pub fn save_configs(&self, report: &MigrationReport) -> AnyResult<VirtualPath> {
+        // Serialize everything up front, so that a failure doesn't
+        // leave the repository in a half-migrated state
+        let mut files = self.render_configs()?;
+
+        self.validate_configs(&files)?;
+
+        // The report is part of the migration, so restoring removes it
+        if !report.is_empty() {
+            files.push((
+                self.root.join("migration-report.json"),
+                json::format(report, true)?,
+            ));
+        }
+
+        let backup_dir = self.create_backup(&files)?;
+
+        for (path, content) in &files {
+            if let Err(error) = fs::write_file(path, content) {
+                self.restore_backup_from(&backup_dir)?;
+
+                return Err(error.into());
+            }
+        }
+
+        Ok(backup_dir)
+    }
+
+    pub fn create_backup(&self, files: &[(VirtualPath, String)]) -> AnyResult<VirtualPath> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let backup_dir = self
+            .root
+            .join(".moon/migrate-backup")
+            .join(timestamp.to_string());
+        let mut backup = MigrationBackup::default();
+
+        for (path, _) in files {
+            let rel_path = self.to_relative_path(path);
+
+            if path.exists() {
+                fs::copy_file(path, backup_dir.join(&rel_path))?;
+                backup.overwritten.push(rel_path);
+            } else {
+                backup.created.push(rel_path);
+            }
+        }
+
+        for path in &self.removed_files {
+            let rel_path = self.to_relative_path(path);
+
+            fs::copy_file(path, backup_dir.join(&rel_path))?;
+            backup.removed.push(rel_path);
+        }
+
+        json::write_file(backup_dir.join("backup.json"), &backup, true)?;
+
+        // Only remove files once the manifest has been saved, otherwise
+        // a failure would lose them without a way to restore
+        for path in &self.removed_files {
+            fs::remove_file(path)?;
+        }
+
+        Ok(backup_dir)
+    }
+
+    pub fn restore_backup(&self) -> AnyResult<Option<VirtualPath>> {
+        let backups_dir = self.root.join(".moon/migrate-backup");
+
+        if !backups_dir.exists() {
+            return Ok(None);
+        }
+
+        // Directories are named by timestamp, so restore the latest
+        let Some(latest) = fs::read_dir(&backups_dir)?
+            .into_iter()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u128>().ok())
+            .max()
+        else {
+            return Ok(None);
+        };
+
+        let backup_dir = backups_dir.join(latest.to_string());
+
+        self.restore_backup_from(&backup_dir)?;
+
+        Ok(Some(backup_dir))
+    }
+
+    pub fn restore_backup_from(&self, backup_dir: &VirtualPath) -> AnyResult<()> {
+        let backup: MigrationBackup = json::read_file(backup_dir.join("backup.json"))?;
+
+        for rel_path in &backup.created {
+            fs::remove_file(self.root.join(rel_path))?;
+        }
+
+        for rel_path in backup.overwritten.iter().chain(&backup.removed) {
+            fs::rename(backup_dir.join(rel_path), self.root.join(rel_path))?;
+        }
+
+        fs::remove_file(backup_dir.join("backup.json"))?;
+
+        remove_empty_dirs(backup_dir)?;
+
         Ok(())
     }
+
+    pub fn to_relative_path(&self, path: &VirtualPath) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Resolve tasks that differ from the existing tasks in place,
+/// and return the IDs of the tasks that should fail the migration.
+fn resolve_conflicting_tasks(
+    existing_tasks: Option<&BTreeMap<Id, PartialTaskConfig>>,
+    tasks: Option<&mut BTreeMap<Id, PartialTaskConfig>>,
+    strategy: TaskConflictStrategy,
+) -> AnyResult<Vec<Id>> {
+    let mut conflicts = vec![];
+
+    let (Some(existing_tasks), Some(tasks)) = (existing_tasks, tasks) else {
+        return Ok(conflicts);
+    };
+
+    for (task_id, existing_task) in existing_tasks {
+        let Some(task) = tasks.get_mut(task_id) else {
+            continue;
+        };
+
+        if task == existing_task {
+            continue;
+        }
+
+        match strategy {
+            TaskConflictStrategy::Error => {
+                conflicts.push(task_id.to_owned());
+            }
+            TaskConflictStrategy::Merge => {
+                let mut merged = existing_task.clone();
+                merged.merge(&(), task.clone())?;
+                merged.deps = merge_lists(&existing_task.deps, &task.deps);
+                merged.inputs = merge_lists(&existing_task.inputs, &task.inputs);
+                merged.outputs = merge_lists(&existing_task.outputs, &task.outputs);
+
+                *task = merged;
+            }
+            TaskConflictStrategy::Overwrite => {}
+            TaskConflictStrategy::Skip => {
+                *task = existing_task.clone();
+            }
+        };
+    }
+
+    Ok(conflicts)
+}
+
+fn merge_lists<T: Clone + PartialEq>(
+    prev: &Option<Vec<T>>,
+    next: &Option<Vec<T>>,
+) -> Option<Vec<T>> {
+    let (Some(prev), Some(next)) = (prev, next) else {
+        return next.clone().or_else(|| prev.clone());
+    };
+
+    let mut list = prev.clone();
+
+    for item in next {
+        if !list.contains(item) {
+            list.push(item.clone());
+        }
+    }
+
+    Some(list)
+}
+
+/// Settings that are hash maps in moon's configs, and would otherwise
+/// be rendered in an arbitrary order. Matched by their path in the config,
+/// where `*` matches any key or list item.
+const HASH_MAP_SETTINGS: &[&str] = &[
+    "constraints/tagRelationships",
+    "env",
+    "extensions",
+    "fileGroups",
+    "owners/customGroups",
+    "project/metadata",
+    "projects",
+    "projects/sources",
+    "tasks/*/deps/*/env",
+    "tasks/*/env",
+    "vcs/hooks",
+    "workspace/inheritedTasks/rename",
+];
+
+/// Render a config with its hash maps sorted by key, so that migrating the
+/// same repository always produces the same output. Existing files are only
+/// edited where they differ, to preserve their comments and formatting.
+fn format_config<T: DeserializeOwned + Serialize>(path: &Path, config: &T) -> AnyResult<String> {
+    let next = to_config_value(config)?;
+
+    if path.exists() {
+        if let Some(content) = merge_config::<T>(&fs::read_file(path)?, &next) {
+            return Ok(content);
+        }
+    }
+
+    Ok(yaml::format(&next)?)
+}
+
+fn format_workspace_config(path: &Path, config: &PartialWorkspaceConfig) -> AnyResult<String> {
+    let mut config = config.clone();
+
+    match &mut config.projects {
+        Some(PartialWorkspaceProjects::Globs(globs))
+        | Some(PartialWorkspaceProjects::Both(PartialWorkspaceProjectsConfig {
+            globs: Some(globs),
+            ..
+        })) => {
+            globs.sort();
+            globs.dedup();
+        }
+        _ => {}
+    };
+
+    format_config(path, &config)
+}
+
+fn merge_config<T: DeserializeOwned + Serialize>(
+    content: &str,
+    next: &YamlValue,
+) -> Option<String> {
+    let prev = to_config_value(&yaml::parse::<_, T>(content).ok()?).ok()?;
+    let merged = merge_yaml(content, &prev, next)?;
+
+    // Fallback to rendering the entire file if the edits are lossy
+    let actual = to_config_value(&yaml::parse::<_, T>(&merged).ok()?).ok()?;
+
+    (actual == *next).then_some(merged)
+}
+
+fn to_config_value<T: Serialize>(config: &T) -> AnyResult<YamlValue> {
+    let mut value = yaml::serde_yaml::to_value(config)?;
+
+    sort_hash_maps(&mut value, &[]);
+
+    Ok(value)
+}
+
+fn sort_hash_maps(value: &mut YamlValue, path: &[&str]) {
+    match value {
+        YamlValue::Mapping(map) => {
+            if is_hash_map_setting(path) {
+                let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+
+                entries.sort_by(|a, b| a.0.as_str().cmp(&b.0.as_str()));
+
+                *map = entries.into_iter().collect();
+            }
+
+            for (key, item) in map.iter_mut() {
+                if let Some(key) = key.as_str() {
+                    sort_hash_maps(item, &[path, &[key]].concat());
+                }
+            }
+        }
+        YamlValue::Sequence(list) => {
+            for item in list {
+                sort_hash_maps(item, &[path, &["*"]].concat());
+            }
+        }
+        _ => {}
+    };
+}
+
+fn is_hash_map_setting(path: &[&str]) -> bool {
+    HASH_MAP_SETTINGS.iter().any(|setting| {
+        let pattern = setting.split('/').collect::<Vec<_>>();
+
+        pattern.len() == path.len()
+            && pattern
+                .iter()
+                .zip(path)
+                .all(|(pattern, segment)| *pattern == "*" || pattern == segment)
+    })
+}
+
+fn validate_config<T: Config>(content: &str) -> Result<(), String> {
+    let partial: T::Partial = yaml::parse(content).map_err(|error| error.to_string())?;
+    let context = <T::Partial as PartialConfig>::Context::default();
+
+    partial
+        .finalize(&context)
+        .and_then(|partial| partial.validate(&context, true))
+        .map_err(|error| error.to_full_string())
+}
+
+// `remove_dir_all` is unreliable within WASI,
+// so remove the now empty directories manually
+fn remove_empty_dirs(dir: &Path) -> AnyResult<()> {
+    for entry in fs::read_dir(dir)? {
+        if entry.file_type()?.is_dir() {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+
+    std::fs::remove_dir(dir)?;
+
+    Ok(())
+}
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
+
+/// Run a migration with the flow that all migration extensions share: undo the
+/// last migration when restoring, otherwise migrate, summarize the report, and
+/// either preview the changes as a diff (dry run), or write them with a backup.
+pub fn run_migration<F>(
+    workspace_root: &VirtualPath,
+    tool_name: &str,
+    dry_run: bool,
+    restore: bool,
+    migrate: F,
+) -> FnResult<()>
+where
+    F: FnOnce() -> FnResult<(Migrator, MigrationReport)>,
+{
+    // Undo the last migration using its backup
+    if restore {
+        let migrator = Migrator::new(workspace_root)?;
+
+        let Some(backup_dir) = migrator.restore_backup()? else {
+            return Err(plugin_err!("No migration backup found to restore."));
+        };
+
+        host_log!(
+            stdout,
+            "Restored original files from <path>{}</path>",
+            migrator.to_relative_path(&backup_dir)
+        );
+
+        return Ok(());
+    }
+
+    let (migrator, mut report) = migrate()?;
+
+    report.sort();
+
+    // Summarize settings that require manual follow-up
+    if !report.is_empty() {
+        host_log!(
+            stdout,
+            "Some settings could not be migrated exactly ({} unsupported, {} approximated) and may require manual changes:",
+            report.count(MigrationReportKind::Unsupported),
+            report.count(MigrationReportKind::Approximated),
+        );
+
+        for entry in &report.entries {
+            host_log!(stdout, "  {}", entry);
+        }
+    }
+
+    // Preview the changes without touching the file system
+    if dry_run {
+        let diff = migrator.diff_configs()?;
+
+        if !diff.is_empty() {
+            host_log!(stdout, "{}", diff);
+        }
+
+        for removed_file in &migrator.removed_files {
+            host_log!(
+                stdout,
+                "Would remove <file>{}</file>",
+                migrator.to_relative_path(removed_file)
+            );
+        }
+
+        host_log!(stdout, "Dry run complete, no files were changed!");
+
+        return Ok(());
+    }
+
+    // Write the new config files
+    let backup_dir = migrator.save_configs(&report)?;
+
+    host_log!(
+        stdout,
+        "Backed up original files to <path>{}</path>, run with <shell>--restore</shell> to undo the migration",
+        migrator.to_relative_path(&backup_dir)
+    );
+
+    if !report.is_empty() {
+        host_log!(
+            stdout,
+            "Wrote a report of these settings to <file>migration-report.json</file>"
+        );
+    }
+
+    host_log!(stdout, "Successfully migrated from {} to moon!", tool_name);
+
+    Ok(())
 }
 
 pub fn create_id<T: AsRef<str>>(id: T) -> AnyResult<Id> {
@@ -140,3 +894,103 @@ pub fn create_id<T: AsRef<str>>(id: T) -> AnyResult<Id> {
         id.as_ref().replace(':', ".").trim_start_matches('@'),
     )?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moon_config::{InputPath, PartialTaskArgs};
+
+    #[test]
+    fn sorts_workspace_project_sources() {
+        let config = PartialWorkspaceConfig {
+            projects: Some(PartialWorkspaceProjects::Sources(FxHashMap::from_iter([
+                (Id::raw("web"), "apps/web".to_owned()),
+                (Id::raw("b"), "packages/b".to_owned()),
+                (Id::raw("a"), "packages/a".to_owned()),
+                (Id::raw("scope/lib"), "packages/lib".to_owned()),
+            ]))),
+            ..PartialWorkspaceConfig::default()
+        };
+
+        assert_eq!(
+            yaml::format(&to_config_value(&config).unwrap()).unwrap(),
+            "projects:\n  a: packages/a\n  b: packages/b\n  scope/lib: packages/lib\n  web: apps/web\n"
+        );
+    }
+
+    #[test]
+    fn only_sorts_hash_maps_by_their_path() {
+        let task = PartialTaskConfig {
+            command: Some(PartialTaskArgs::String("vite".into())),
+            args: Some(PartialTaskArgs::String("build".into())),
+            env: Some(FxHashMap::from_iter([
+                ("NODE_ENV".to_owned(), "production".to_owned()),
+                ("API_URL".to_owned(), "localhost".to_owned()),
+            ])),
+            ..PartialTaskConfig::default()
+        };
+        let config = PartialProjectConfig {
+            tasks: Some(BTreeMap::from_iter([
+                (Id::raw("config"), task.clone()),
+                (Id::raw("env"), task),
+            ])),
+            ..PartialProjectConfig::default()
+        };
+
+        let task_yaml = "    command: vite\n    args: build\n    env:\n      API_URL: localhost\n      NODE_ENV: production\n";
+
+        assert_eq!(
+            yaml::format(&to_config_value(&config).unwrap()).unwrap(),
+            format!("tasks:\n  config:\n{task_yaml}  env:\n{task_yaml}")
+        );
+    }
+
+    #[test]
+    fn matches_wildcards_in_hash_map_settings() {
+        assert!(is_hash_map_setting(&["tasks", "build", "env"]));
+        assert!(is_hash_map_setting(&["tasks", "build", "deps", "*", "env"]));
+        assert!(!is_hash_map_setting(&["tasks", "env"]));
+        assert!(!is_hash_map_setting(&["tasks", "build", "options"]));
+        assert!(!is_hash_map_setting(&["config"]));
+    }
+
+    #[test]
+    fn merges_configs_in_place() {
+        let content = "# Comment\ntasks:\n  lint:\n    command: eslint # Inline\n";
+        let mut config = yaml::parse::<_, PartialProjectConfig>(content).unwrap();
+
+        config.tasks.as_mut().unwrap().insert(
+            Id::raw("test"),
+            PartialTaskConfig {
+                command: Some(PartialTaskArgs::String("jest".into())),
+                ..PartialTaskConfig::default()
+            },
+        );
+
+        assert_eq!(
+            merge_config::<PartialProjectConfig>(content, &to_config_value(&config).unwrap())
+                .unwrap(),
+            "# Comment\ntasks:\n  lint:\n    command: eslint # Inline\n  test:\n    command: jest\n"
+        );
+    }
+
+    #[test]
+    fn falls_back_if_merged_config_is_lossy() {
+        // Replacing the anchored file group drops the anchor of the alias
+        let content = "fileGroups:\n  sources: &sources\n    - src/**/*\ntasks:\n  lint:\n    command: eslint\n    inputs: *sources\n";
+        let mut config = yaml::parse::<_, PartialProjectConfig>(content).unwrap();
+
+        config
+            .file_groups
+            .as_mut()
+            .unwrap()
+            .get_mut("sources")
+            .unwrap()
+            .push(InputPath::ProjectGlob("lib/**/*".into()));
+
+        assert_eq!(
+            merge_config::<PartialProjectConfig>(content, &to_config_value(&config).unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// Only type fields we actually need!
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum PackageWorkspaces {
+    List(Vec<String>),
+    // Yarn v1
+    Object { packages: Vec<String> },
+}
+
+impl PackageWorkspaces {
+    pub fn get_globs(&self) -> &[String] {
+        match self {
+            Self::List(globs) => globs,
+            Self::Object { packages } => packages,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJson {
+    pub name: Option<String>,
+    pub scripts: Option<BTreeMap<String, String>>,
+    pub workspaces: Option<PackageWorkspaces>,
+}
+
+/// npm lifecycle scripts that are ran automatically by the package manager,
+/// and should not be converted into tasks.
+pub const LIFECYCLE_SCRIPTS: [&str; 11] = [
+    "install",
+    "postinstall",
+    "postpack",
+    "postpublish",
+    "preinstall",
+    "prepack",
+    "prepare",
+    "prepublish",
+    "prepublishOnly",
+    "preuninstall",
+    "publish",
+];
+
+/// Convert workspace package globs into globs that match
+/// the `package.json` of each package, preserving negations.
+pub fn create_package_json_globs(package_globs: &[String]) -> Vec<String> {
+    let mut globs = vec![];
+
+    for glob in package_globs {
+        if let Some(negated_glob) = glob.strip_prefix('!') {
+            globs.push(format!(
+                "!{}/package.json",
+                negated_glob.trim_end_matches('/')
+            ));
+        } else {
+            globs.push(format!("{}/package.json", glob.trim_end_matches('/')));
+        }
+    }
+
+    globs.push("!**/node_modules/**/*".into());
+    globs
+}
@@ -1,5 +1,19 @@
+use crate::migrator::Migrator;
+use extism_pdk::*;
+use moon_config::{
+    PartialProjectDependsOn, PartialTaskConfig, PartialTaskDependency, PartialWorkspaceProjects,
+};
+use moon_pdk::{anyhow, host_log, AnyResult, HostLogInput, HostLogTarget, VirtualPath};
+use moon_target::{Target, TargetScope};
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
 
 // Only type fields we actually need!
 
@@ -16,3 +30,174 @@ pub struct Project {
 pub struct ProjectGraph {
     pub projects: FxHashMap<String, Project>,
 }
+
+impl ProjectGraph {
+    /// Load the project graph that moon cached during its last run, if it has
+    /// been ran in the workspace before, otherwise the graph is empty.
+    pub fn load_cached(workspace_root: &VirtualPath) -> AnyResult<Self> {
+        let cache_path = workspace_root.join(".moon/cache/states/projectGraph.json");
+
+        if !cache_path.exists() {
+            return Ok(Self::default());
+        }
+
+        // The cache is internal to moon and its shape may change between
+        // versions, so don't fail the migration when it can't be parsed
+        match json::read_file(&cache_path) {
+            Ok(graph) => Ok(graph),
+            Err(error) => {
+                host_log!(
+                    stderr,
+                    "Unable to parse the cached project graph <path>.moon/cache/states/projectGraph.json</path>, only migrated projects will be known: {}",
+                    error
+                );
+
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Load the cached project graph, and add all projects that are
+    /// being migrated, either explicitly or with a config file.
+    pub fn load_with_migrated(migrator: &Migrator) -> AnyResult<Self> {
+        let mut graph = Self::load_cached(&migrator.root)?;
+
+        if let Some(PartialWorkspaceProjects::Sources(sources)) = migrator
+            .workspace_config
+            .as_ref()
+            .and_then(|config| config.projects.as_ref())
+        {
+            for (id, source) in sources {
+                graph.add_project(id, source, None);
+            }
+        }
+
+        for config_path in migrator.project_configs.keys() {
+            let project_root = config_path.parent().unwrap();
+            let source = project_root
+                .strip_prefix(&migrator.root)
+                .unwrap_or(project_root)
+                .to_string_lossy();
+
+            graph.add_project(migrator.get_project_id(config_path), source, None);
+        }
+
+        Ok(graph)
+    }
+
+    pub fn add_project(
+        &mut self,
+        id: impl AsRef<str>,
+        source: impl AsRef<str>,
+        alias: Option<String>,
+    ) {
+        let id = id.as_ref();
+
+        self.projects.insert(
+            id.to_owned(),
+            Project {
+                alias,
+                id: id.to_owned(),
+                source: source.as_ref().to_owned(),
+            },
+        );
+    }
+
+    /// Find a project by its ID or alias.
+    pub fn find_project(&self, id_or_alias: &str) -> Option<&Project> {
+        self.projects.get(id_or_alias).or_else(|| {
+            self.projects
+                .values()
+                .find(|project| project.alias.as_deref() == Some(id_or_alias))
+        })
+    }
+
+    /// Find project dependencies and task targets that reference projects
+    /// that don't exist in the graph, grouped by the config file.
+    pub fn find_dangling_references(&self, migrator: &Migrator) -> BTreeMap<String, Vec<String>> {
+        let mut dangling = BTreeMap::<String, Vec<String>>::default();
+
+        let mut check_tasks = |file: String, tasks: Option<&BTreeMap<_, PartialTaskConfig>>| {
+            for (task_id, task) in tasks.into_iter().flatten() {
+                for dep in task.deps.iter().flatten() {
+                    let target = match dep {
+                        PartialTaskDependency::Target(target) => Some(target),
+                        PartialTaskDependency::Config(config) => config.target.as_ref(),
+                    };
+
+                    if let Some(Target {
+                        scope: TargetScope::Project(id),
+                        id: target_id,
+                        ..
+                    }) = target
+                    {
+                        if self.find_project(id).is_none() {
+                            dangling
+                                .entry(file.clone())
+                                .or_default()
+                                .push(format!("<id>{target_id}</id> in task <id>{task_id}</id>"));
+                        }
+                    }
+                }
+            }
+        };
+
+        for (config_path, config) in &migrator.tasks_configs {
+            check_tasks(
+                migrator.to_relative_path(config_path),
+                config.tasks.as_ref(),
+            );
+        }
+
+        for (config_path, config) in &migrator.project_configs {
+            check_tasks(
+                migrator.to_relative_path(config_path),
+                config.tasks.as_ref(),
+            );
+        }
+
+        for (config_path, config) in &migrator.project_configs {
+            for dep in config.depends_on.iter().flatten() {
+                let id = match dep {
+                    PartialProjectDependsOn::String(id) => Some(id),
+                    PartialProjectDependsOn::Object(dep) => dep.id.as_ref(),
+                };
+
+                if let Some(id) = id {
+                    if self.find_project(id).is_none() {
+                        dangling
+                            .entry(migrator.to_relative_path(config_path))
+                            .or_default()
+                            .push(format!("<id>{id}</id> in dependsOn"));
+                    }
+                }
+            }
+        }
+
+        for references in dangling.values_mut() {
+            references.sort();
+        }
+
+        dangling
+    }
+
+    /// Ensure that all references to projects exist, before configs are written.
+    pub fn validate_references(&self, migrator: &Migrator) -> AnyResult<()> {
+        let dangling = self.find_dangling_references(migrator);
+
+        if dangling.is_empty() {
+            return Ok(());
+        }
+
+        let mut message =
+            String::from("Unable to migrate, as configs reference projects that do not exist:");
+
+        for (file, references) in dangling {
+            for reference in references {
+                message.push_str(&format!("\n  <file>{file}</file>: {reference}"));
+            }
+        }
+
+        Err(anyhow!(message))
+    }
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MigrationReportKind {
+    /// The setting was migrated, but moon's behavior may differ.
+    Approximated,
+    /// The setting was dropped, as moon has no equivalent.
+    Unsupported,
+}
+
+impl fmt::Display for MigrationReportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Approximated => write!(f, "approximated"),
+            Self::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MigrationReportEntry {
+    pub kind: MigrationReportKind,
+    pub file: String,
+    pub pointer: String,
+    pub reason: String,
+}
+
+impl fmt::Display for MigrationReportEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] <file>{}</file> <property>{}</property> {}",
+            self.kind, self.file, self.pointer, self.reason
+        )
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MigrationReport {
+    pub entries: Vec<MigrationReportEntry>,
+}
+
+impl MigrationReport {
+    pub fn approximated<F: AsRef<str>, P: AsRef<str>, R: AsRef<str>>(
+        &mut self,
+        file: F,
+        pointer: P,
+        reason: R,
+    ) {
+        self.add(MigrationReportKind::Approximated, file, pointer, reason);
+    }
+
+    pub fn unsupported<F: AsRef<str>, P: AsRef<str>, R: AsRef<str>>(
+        &mut self,
+        file: F,
+        pointer: P,
+        reason: R,
+    ) {
+        self.add(MigrationReportKind::Unsupported, file, pointer, reason);
+    }
+
+    pub fn add<F: AsRef<str>, P: AsRef<str>, R: AsRef<str>>(
+        &mut self,
+        kind: MigrationReportKind,
+        file: F,
+        pointer: P,
+        reason: R,
+    ) {
+        let entry = MigrationReportEntry {
+            kind,
+            file: file.as_ref().to_owned(),
+            pointer: pointer.as_ref().to_owned(),
+            reason: reason.as_ref().to_owned(),
+        };
+
+        // The same setting may be migrated more than once
+        if !self.entries.contains(&entry) {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Sort entries by file and pointer, so that the report doesn't depend
+    /// on the order that settings were migrated in. Entries of the same
+    /// setting keep their order.
+    pub fn sort(&mut self) {
+        self.entries
+            .sort_by(|a, b| (&a.file, &a.pointer).cmp(&(&b.file, &b.pointer)));
+    }
+
+    pub fn count(&self, kind: MigrationReportKind) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Create a JSON pointer (RFC 6901) from the provided path segments.
+pub fn create_pointer<I, T>(segments: I) -> String
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut pointer = String::new();
+
+    for segment in segments {
+        pointer.push('/');
+        pointer.push_str(&segment.as_ref().replace('~', "~0").replace('/', "~1"));
+    }
+
+    pointer
+}
@@ -0,0 +1,110 @@
+use crate::migrator::create_id;
+use crate::package_json::PackageJson;
+use moon_common::Id;
+use moon_pdk::{anyhow, map_miette_error, AnyResult, VirtualPath};
+use moon_target::Target;
+use starbase_utils::{fs, glob, json};
+use std::collections::BTreeSet;
+
+pub struct PackageEntry {
+    pub id: Id,
+    pub alias: String,
+    pub source: String,
+}
+
+/// A dependency within a Turborepo-style pipeline (also used by Lage).
+pub enum PipelineDependency {
+    /// `$ENV`, which is converted to an input.
+    EnvVar(String),
+    /// `^task`, `package#task`, or `task`.
+    Target(Target),
+}
+
+/// Named packages found within the workspace.
+pub struct WorkspacePackages {
+    pub globs: BTreeSet<String>,
+    pub packages: Vec<PackageEntry>,
+}
+
+impl WorkspacePackages {
+    pub fn load(workspace_root: &VirtualPath) -> AnyResult<Self> {
+        let mut packages = vec![];
+        let mut globs = BTreeSet::default();
+
+        for package_json_path in glob::walk_files(
+            workspace_root,
+            ["**/*/package.json", "!**/node_modules/**/*"],
+        )? {
+            let package_json: PackageJson = json::read_file(&package_json_path)?;
+
+            if let Some(name) = package_json.name {
+                let package_root = package_json_path.parent().unwrap();
+                let package_source = package_root.strip_prefix(workspace_root).unwrap();
+
+                globs.insert(format!(
+                    "{}/*",
+                    package_source.parent().unwrap().to_string_lossy()
+                ));
+
+                packages.push(PackageEntry {
+                    id: create_id(fs::file_name(package_root))?,
+                    alias: name,
+                    source: package_source.to_string_lossy().to_string(),
+                });
+            }
+        }
+
+        Ok(Self { globs, packages })
+    }
+
+    pub fn find_package(&self, package_name: &str) -> AnyResult<&PackageEntry> {
+        for package in &self.packages {
+            if package.id == package_name || package.alias == package_name {
+                return Ok(package);
+            }
+        }
+
+        Err(anyhow!("Unable to migrate task as package <id>{package_name}</id> does not exist. Is it within the workspace?"))
+    }
+
+    /// Find the package and script of a `package#script` reference.
+    pub fn find_package_script(&self, script: &str) -> AnyResult<(&PackageEntry, String)> {
+        let mut parts = script.split('#');
+        let package_name = parts.next().unwrap();
+        let script_name = parts.next().unwrap();
+        let package = self.find_package(package_name)?;
+
+        Ok((package, script_name.to_owned()))
+    }
+
+    pub fn parse_dependency(&self, dep: &str) -> AnyResult<PipelineDependency> {
+        // $ENV input
+        if let Some(env) = dep.strip_prefix('$') {
+            return Ok(PipelineDependency::EnvVar(env.into()));
+        }
+
+        // ^:task
+        if let Some(dep) = dep.strip_prefix('^') {
+            return Ok(PipelineDependency::Target(
+                Target::parse(format!("^:{}", create_id(dep)?).as_str())
+                    .map_err(map_miette_error)?,
+            ));
+        }
+
+        // project:task
+        if dep.contains('#') {
+            let (package, script) = self.find_package_script(dep)?;
+            let task_id = create_id(&script)?;
+
+            return Ok(PipelineDependency::Target(
+                Target::parse(format!("{}:{task_id}", package.id).as_str())
+                    .map_err(map_miette_error)?,
+            ));
+        }
+
+        // ~:task
+        Ok(PipelineDependency::Target(
+            Target::new_self(create_id(dep)?).map_err(map_miette_error)?,
+        ))
+    }
+}
@@ -0,0 +1,543 @@
+use starbase_utils::yaml::{self, YamlMapping, YamlValue};
+
+// A minimal editor for block-style YAML documents. Only the entries whose
+// value changed are re-rendered, so that comments, anchors, key order, and
+// formatting of everything else is preserved byte-for-byte.
+
+enum EntryValue {
+    /// The value is on the same line as the key (scalars, flow collections,
+    /// block scalars, aliases).
+    Inline,
+    /// A nested block mapping on the following lines.
+    Mapping(usize),
+    /// A nested block sequence, or a value with an anchor or tag.
+    Opaque,
+}
+
+struct Entry {
+    key: String,
+    /// Line of the key.
+    start: usize,
+    /// Line after the last line of the value, excluding trailing comments.
+    end: usize,
+    value: EntryValue,
+}
+
+struct Edit {
+    start: usize,
+    end: usize,
+    content: String,
+}
+
+/// Merge the next value into the existing YAML content, by only replacing,
+/// removing, or appending the entries that differ from the previous value.
+/// Returns `None` if the content can't be edited in place.
+pub fn merge_yaml(content: &str, prev: &YamlValue, next: &YamlValue) -> Option<String> {
+    let (YamlValue::Mapping(prev), YamlValue::Mapping(next)) = (prev, next) else {
+        return None;
+    };
+
+    let mut lines = content
+        .split_inclusive('\n')
+        .map(|line| line.to_owned())
+        .collect::<Vec<_>>();
+    let mut edits = vec![];
+
+    merge_mapping(&lines, 0, lines.len(), 0, prev, next, &mut edits)?;
+
+    if edits.is_empty() {
+        return Some(content.to_owned());
+    }
+
+    if let Some(last) = lines.last_mut() {
+        if !last.ends_with('\n') {
+            last.push('\n');
+        }
+    }
+
+    // Apply from the bottom up, so that line numbers remain valid, and
+    // nested entries are appended before the entries of their parents
+    edits.sort_by_key(|edit| edit.start);
+
+    for edit in edits.into_iter().rev() {
+        lines.splice(edit.start..edit.end, [edit.content]);
+    }
+
+    let output = lines.concat();
+
+    Some(if content.contains("\r\n") {
+        output.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        output
+    })
+}
+
+fn merge_mapping(
+    lines: &[String],
+    start: usize,
+    end: usize,
+    indent: usize,
+    prev: &YamlMapping,
+    next: &YamlMapping,
+    edits: &mut Vec<Edit>,
+) -> Option<()> {
+    let entries = parse_mapping(lines, start, end, indent)?;
+    let mut appended = String::new();
+
+    for (key, next_value) in next {
+        let key = key.as_str()?;
+        let prev_value = prev.get(key);
+
+        let Some(entry) = entries.iter().find(|entry| entry.key == key) else {
+            appended.push_str(&render_entry(key, next_value, indent)?);
+            continue;
+        };
+
+        if prev_value == Some(next_value) {
+            continue;
+        }
+
+        match (&entry.value, prev_value, next_value) {
+            (
+                EntryValue::Mapping(child_indent),
+                Some(YamlValue::Mapping(prev_map)),
+                YamlValue::Mapping(next_map),
+            ) => {
+                merge_mapping(
+                    lines,
+                    entry.start + 1,
+                    entry.end,
+                    *child_indent,
+                    prev_map,
+                    next_map,
+                    edits,
+                )?;
+            }
+            _ => {
+                edits.push(Edit {
+                    start: entry.start,
+                    end: entry.end,
+                    content: render_entry(key, next_value, indent)?,
+                });
+            }
+        };
+    }
+
+    // Remove entries that were removed from the config, but keep keys
+    // that moon doesn't know about, as they were never loaded
+    for entry in &entries {
+        if prev.contains_key(entry.key.as_str()) && !next.contains_key(entry.key.as_str()) {
+            edits.push(Edit {
+                start: entry.start,
+                end: entry.end,
+                content: String::new(),
+            });
+        }
+    }
+
+    if !appended.is_empty() {
+        let position = entries.last().map(|entry| entry.end)?;
+
+        edits.push(Edit {
+            start: position,
+            end: position,
+            content: appended,
+        });
+    }
+
+    Some(())
+}
+
+fn parse_mapping(lines: &[String], start: usize, end: usize, indent: usize) -> Option<Vec<Entry>> {
+    let mut entries: Vec<Entry> = vec![];
+    let mut index = start;
+
+    while index < end {
+        let line = lines[index].trim_end();
+
+        if is_ignored_line(line) {
+            index += 1;
+            continue;
+        }
+
+        if get_indent(line)? != indent {
+            return None;
+        }
+
+        let (key, rest) = parse_key(&line[indent..])?;
+        let rest = strip_comment(rest).trim();
+
+        // Find the last line of the value, which is indented further,
+        // or a sequence at the same indentation as the key
+        let mut entry_end = index + 1;
+        let mut last_content = index + 1;
+
+        while entry_end < end {
+            let next_line = lines[entry_end].trim_end();
+
+            if !is_ignored_line(next_line) {
+                let next_indent = get_indent(next_line)?;
+                let is_sequence = rest.is_empty()
+                    && next_indent == indent
+                    && (next_line[indent..].starts_with("- ") || &next_line[indent..] == "-");
+
+                if next_indent <= indent && !is_sequence {
+                    break;
+                }
+
+                last_content = entry_end + 1;
+            }
+
+            entry_end += 1;
+        }
+
+        let value = if rest.is_empty() {
+            match lines[index + 1..last_content]
+                .iter()
+                .map(|line| line.trim_end())
+                .find(|line| !is_ignored_line(line))
+            {
+                Some(child) => {
+                    let child_indent = get_indent(child)?;
+                    let child = &child[child_indent..];
+
+                    if child_indent > indent && !child.starts_with('-') {
+                        EntryValue::Mapping(child_indent)
+                    } else {
+                        EntryValue::Opaque
+                    }
+                }
+                None => EntryValue::Inline,
+            }
+        } else if rest.starts_with('&') || rest.starts_with('!') {
+            EntryValue::Opaque
+        } else {
+            EntryValue::Inline
+        };
+
+        if entries.iter().any(|entry| entry.key == key) {
+            return None;
+        }
+
+        entries.push(Entry {
+            key,
+            start: index,
+            end: last_content,
+            value,
+        });
+
+        index = last_content;
+    }
+
+    Some(entries)
+}
+
+fn parse_key(line: &str) -> Option<(String, &str)> {
+    let (key, rest) = if line.starts_with('"') || line.starts_with('\'') {
+        let quote = line.chars().next()?;
+        let mut close = None;
+        let mut chars = line.char_indices().skip(1).peekable();
+
+        while let Some((index, char)) = chars.next() {
+            if quote == '"' && char == '\\' {
+                chars.next();
+            } else if char == quote {
+                // Single quotes are escaped by doubling them
+                if quote == '\'' && chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                    chars.next();
+                } else {
+                    close = Some(index);
+                    break;
+                }
+            }
+        }
+
+        let close = close?;
+        let key = yaml::parse::<_, String>(&line[..=close]).ok()?;
+
+        (key, line[close + 1..].trim_start().strip_prefix(':')?)
+    } else {
+        let position = line
+            .match_indices(':')
+            .map(|(index, _)| index)
+            .find(|index| {
+                line[index + 1..]
+                    .chars()
+                    .next()
+                    .map_or(true, |char| char == ' ' || char == '\t')
+            })?;
+        let key = line[..position].trim_end();
+
+        if key.is_empty()
+            || key == "<<"
+            || key.contains(" #")
+            || key.starts_with(['-', '?', '&', '*', '!', '|', '>', '{', '[', '%', '@', '`'])
+        {
+            return None;
+        }
+
+        (key.to_owned(), &line[position + 1..])
+    };
+
+    Some((key, rest))
+}
+
+fn render_entry(key: &str, value: &YamlValue, indent: usize) -> Option<String> {
+    let mut map = YamlMapping::new();
+    map.insert(YamlValue::String(key.to_owned()), value.clone());
+
+    let prefix = " ".repeat(indent);
+    let mut output = String::new();
+
+    for line in yaml::format(&map).ok()?.lines() {
+        if !line.is_empty() {
+            output.push_str(&prefix);
+            output.push_str(line);
+        }
+
+        output.push('\n');
+    }
+
+    Some(output)
+}
+
+fn strip_comment(value: &str) -> &str {
+    if value.trim_start().starts_with('#') {
+        ""
+    } else {
+        value
+    }
+}
+
+fn get_indent(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+
+    // Tabs are not valid indentation in YAML
+    if line[indent..].starts_with('\t') {
+        return None;
+    }
+
+    Some(indent)
+}
+
+fn is_ignored_line(line: &str) -> bool {
+    let line = line.trim();
+
+    line.is_empty() || line.starts_with('#') || line == "---"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(content: &str, next: &str) -> Option<String> {
+        let prev = yaml::parse::<_, YamlValue>(content).unwrap();
+        let next = yaml::parse::<_, YamlValue>(next).unwrap();
+
+        merge_yaml(content, &prev, &next)
+    }
+
+    #[test]
+    fn merges_project_config() {
+        let content = r#"# Project settings
+language: "typescript"
+
+tasks:
+  # Builds the app
+  build:
+    command: "vite build" # uses vite.config.ts
+    outputs:
+      - "dist"
+
+  dev:
+    command: "vite"
+    local: true
+"#;
+
+        assert_eq!(
+            merge(
+                content,
+                r#"
+language: typescript
+type: application
+tasks:
+  build:
+    command: vite build
+    outputs: [dist, stats.json]
+  test:
+    command: vitest
+"#
+            )
+            .unwrap(),
+            r#"# Project settings
+language: "typescript"
+
+tasks:
+  # Builds the app
+  build:
+    command: "vite build" # uses vite.config.ts
+    outputs:
+    - dist
+    - stats.json
+
+  test:
+    command: vitest
+type: application
+"#
+        );
+    }
+
+    #[test]
+    fn returns_content_if_nothing_changed() {
+        let content = "tasks:\n  build:\n    command: \"vite build\" # comment\n";
+
+        assert_eq!(
+            merge(content, "tasks:\n  build:\n    command: vite build\n").unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn preserves_comments_between_keys() {
+        let content = r#"tasks:
+  # First
+  lint:
+    command: eslint
+
+  # Second
+  # (multiple lines)
+  test:
+    # Nested
+    command: jest
+    # Trailing
+"#;
+
+        assert_eq!(
+            merge(
+                content,
+                "tasks:\n  lint:\n    command: eslint\n  test:\n    command: jest\n    args: --ci\n"
+            )
+            .unwrap(),
+            r#"tasks:
+  # First
+  lint:
+    command: eslint
+
+  # Second
+  # (multiple lines)
+  test:
+    # Nested
+    command: jest
+    args: --ci
+    # Trailing
+"#
+        );
+    }
+
+    #[test]
+    fn preserves_anchors_and_aliases() {
+        let content = r#"fileGroups:
+  sources: &sources
+    - "src/**/*"
+
+tasks:
+  lint:
+    command: eslint
+    inputs: *sources
+"#;
+
+        assert_eq!(
+            merge(
+                content,
+                r#"
+fileGroups:
+  sources: ["src/**/*"]
+tasks:
+  lint:
+    command: eslint
+    inputs: ["src/**/*"]
+  build:
+    command: tsc
+"#
+            )
+            .unwrap(),
+            r#"fileGroups:
+  sources: &sources
+    - "src/**/*"
+
+tasks:
+  lint:
+    command: eslint
+    inputs: *sources
+  build:
+    command: tsc
+"#
+        );
+    }
+
+    #[test]
+    fn replaces_flow_and_empty_mappings() {
+        let content = "tasks: {}\nfileGroups: { sources: [src] }\nenv:\n";
+
+        assert_eq!(
+            merge(
+                content,
+                "tasks:\n  build:\n    command: tsc\nfileGroups:\n  sources: [src, lib]\nenv:\n"
+            )
+            .unwrap(),
+            "tasks:\n  build:\n    command: tsc\nfileGroups:\n  sources:\n  - src\n  - lib\nenv:\n"
+        );
+    }
+
+    #[test]
+    fn preserves_flow_values_that_didnt_change() {
+        let content = "tasks: {}\nimplicitDeps: [a, b]\n";
+
+        assert_eq!(
+            merge(content, "tasks: {}\nimplicitDeps: [a, b]\nlanguage: rust\n").unwrap(),
+            "tasks: {}\nimplicitDeps: [a, b]\nlanguage: rust\n"
+        );
+    }
+
+    #[test]
+    fn preserves_crlf_line_endings() {
+        let content = "tasks:\r\n  lint:\r\n    command: eslint\r\n";
+
+        assert_eq!(
+            merge(
+                content,
+                "tasks:\n  lint:\n    command: eslint\n  test:\n    command: jest\n"
+            )
+            .unwrap(),
+            "tasks:\r\n  lint:\r\n    command: eslint\r\n  test:\r\n    command: jest\r\n"
+        );
+    }
+
+    #[test]
+    fn returns_none_if_content_cant_be_edited() {
+        let next = yaml::parse::<_, YamlValue>("tasks:\n  build:\n    command: tsc\n").unwrap();
+
+        // Duplicate keys
+        assert!(merge_yaml(
+            "tasks:\n  lint:\n    command: eslint\ntasks:\n  test: {}\n",
+            &YamlValue::Mapping(YamlMapping::new()),
+            &next
+        )
+        .is_none());
+
+        // Tab indentation
+        assert!(merge_yaml(
+            "tasks:\n\tlint:\n\t\tcommand: eslint\n",
+            &YamlValue::Mapping(YamlMapping::new()),
+            &next
+        )
+        .is_none());
+
+        // Flow mapping documents
+        assert!(merge("{ tasks: { lint: { command: eslint } } }", "tasks: {}").is_none());
+
+        // Empty documents
+        assert!(merge_yaml("# Empty\n", &YamlValue::Null, &next).is_none());
+        assert!(merge_yaml("# Empty\n", &YamlValue::Mapping(YamlMapping::new()), &next).is_none());
+    }
+}
@@ -0,0 +1,62 @@
+// https://microsoft.github.io/lage/docs/Reference/config
+
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use starbase_utils::json::JsonValue;
+use std::collections::BTreeMap;
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LageTarget {
+    pub cache: Option<bool>,
+    pub depends_on: Option<Vec<String>>,
+    pub inputs: Option<Vec<String>>,
+    pub outputs: Option<Vec<String>>,
+    pub priority: Option<i64>,
+    #[serde(rename = "type")]
+    pub type_of: Option<String>,
+    // Not supported:
+    // options, weight, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum LagePipelineEntry {
+    Deps(Vec<String>),
+    Target(LageTarget),
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LageCacheOptions {
+    pub environment_glob: Option<Vec<String>>,
+    pub output_glob: Option<Vec<String>>,
+    // Not supported:
+    // cacheStorageConfig, writeRemoteCache, skipLocalCache, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagePriority {
+    pub package: Option<String>,
+    pub priority: i64,
+    pub task: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LageConfig {
+    pub cache_options: Option<LageCacheOptions>,
+    pub npm_client: Option<String>,
+    pub pipeline: Option<BTreeMap<String, LagePipelineEntry>>,
+    pub priorities: Option<Vec<LagePriority>>,
+    // Not supported:
+    // concurrency, ignore, repoWideChanges, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
@@ -0,0 +1,303 @@
+use crate::lage_config::*;
+use moon_config::{
+    InputPath, NodePackageManager, OutputPath, PartialNodeConfig, PartialTaskArgs,
+    PartialTaskConfig, PartialTaskDependency, PartialTaskOptionsConfig, PartialWorkspaceProjects,
+    PlatformType,
+};
+use moon_extension_common::migrator::*;
+use moon_extension_common::report::*;
+use moon_extension_common::workspace_packages::*;
+use moon_pdk::{AnyResult, MoonContext};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub struct LageMigrator {
+    pub inner: Migrator,
+    /// Default outputs from `cacheOptions.outputGlob`.
+    pub output_globs: Vec<String>,
+    pub package_manager: String,
+    pub packages: WorkspacePackages,
+    pub report: MigrationReport,
+}
+
+impl LageMigrator {
+    pub fn new(context: &MoonContext, bun: bool) -> AnyResult<Self> {
+        let mut migrator = Migrator::new(&context.workspace_root)?;
+
+        if bun {
+            migrator.platform = PlatformType::Bun;
+        }
+
+        Ok(Self {
+            output_globs: vec![],
+            package_manager: migrator.detect_package_manager(),
+            packages: WorkspacePackages::load(&context.workspace_root)?,
+            inner: migrator,
+            report: MigrationReport::default(),
+        })
+    }
+
+    pub fn migrate_config(&mut self, lage_config: LageConfig, file: &str) -> AnyResult<()> {
+        for key in lage_config.unknown.keys() {
+            let reason = match key.as_str() {
+                "$schema" => continue,
+                "ignore" => "Use task inputs to control affected files instead",
+                "repoWideChanges" => {
+                    "Use implicit inputs in .moon/tasks to affect all projects instead"
+                }
+                _ => "Setting is not supported",
+            };
+
+            self.report.unsupported(file, create_pointer([key]), reason);
+        }
+
+        // Package manager
+        if let Some(npm_client) = &lage_config.npm_client {
+            // Bun takes precedence when forced
+            if !matches!(self.inner.platform, PlatformType::Bun) {
+                let package_manager = match npm_client.as_str() {
+                    "bun" => Some(NodePackageManager::Bun),
+                    "npm" => Some(NodePackageManager::Npm),
+                    "pnpm" => Some(NodePackageManager::Pnpm),
+                    "yarn" => Some(NodePackageManager::Yarn),
+                    _ => None,
+                };
+
+                if package_manager.is_some() {
+                    self.package_manager = npm_client.to_owned();
+
+                    self.inner
+                        .load_toolchain_config()?
+                        .node
+                        .get_or_insert(PartialNodeConfig::default())
+                        .package_manager = package_manager;
+                } else {
+                    self.report.unsupported(
+                        file,
+                        "/npmClient",
+                        format!("Package manager {npm_client} is not supported"),
+                    );
+                }
+            }
+        }
+
+        // Cache
+        if let Some(cache_options) = lage_config.cache_options {
+            if let Some(environment_glob) = cache_options.environment_glob {
+                let mut implicit_inputs = vec![];
+
+                // Globs are relative to the workspace root
+                for glob in environment_glob {
+                    implicit_inputs.push(InputPath::from_str(&format!("/{glob}"))?);
+                }
+
+                if !implicit_inputs.is_empty() {
+                    self.inner
+                        .load_tasks_platform_config()?
+                        .implicit_inputs
+                        .get_or_insert(vec![])
+                        .extend(implicit_inputs);
+                }
+            }
+
+            if let Some(output_glob) = cache_options.output_glob {
+                self.output_globs = output_glob;
+            }
+
+            for key in cache_options.unknown.keys() {
+                let reason = match key.as_str() {
+                    "cacheStorageConfig" | "writeRemoteCache" => {
+                        "Remote caching is configured in .moon/workspace.yml instead"
+                    }
+                    _ => "Setting is not supported",
+                };
+
+                self.report
+                    .unsupported(file, create_pointer(["cacheOptions", key]), reason);
+            }
+        }
+
+        // Packages
+        if !self.packages.globs.is_empty() {
+            let workspace_config = self.inner.load_workspace_config()?;
+
+            if workspace_config.projects.is_none() {
+                workspace_config.projects = Some(PartialWorkspaceProjects::Globs(
+                    self.packages.globs.clone().into_iter().collect(),
+                ));
+            }
+        }
+
+        // Pipeline
+        for (key, entry) in lage_config.pipeline.unwrap_or_default() {
+            let pointer = create_pointer(["pipeline", &key]);
+            let target = match entry {
+                LagePipelineEntry::Deps(deps) => LageTarget {
+                    depends_on: Some(deps),
+                    ..LageTarget::default()
+                },
+                LagePipelineEntry::Target(target) => target,
+            };
+
+            // Project-scoped task
+            if key.contains('#') {
+                let (project_source, script_name) = self
+                    .packages
+                    .find_package_script(&key)
+                    .map(|(p, i)| (p.source.to_owned(), i))?;
+
+                let task = self.migrate_task(target, &script_name, file, &pointer)?;
+
+                self.inner
+                    .load_project_config(&project_source)?
+                    .tasks
+                    .get_or_insert(BTreeMap::default())
+                    .insert(create_id(&script_name)?, task);
+            }
+            // Global task
+            else {
+                let task = self.migrate_task(target, &key, file, &pointer)?;
+
+                self.inner
+                    .load_tasks_platform_config()?
+                    .tasks
+                    .get_or_insert(BTreeMap::default())
+                    .insert(create_id(&key)?, task);
+            }
+        }
+
+        // Priorities
+        for (index, priority) in lage_config
+            .priorities
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(package) = &priority.package {
+                self.packages.find_package(package)?;
+            }
+
+            self.report.unsupported(
+                file,
+                create_pointer(["priorities", &index.to_string()]),
+                "Task priorities are not supported, tasks are scheduled by their dependencies",
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn migrate_task(
+        &mut self,
+        lage_target: LageTarget,
+        package_script: &str,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<PartialTaskConfig> {
+        let mut config = PartialTaskConfig::default();
+        let mut inputs = vec![];
+
+        config.command = Some(PartialTaskArgs::String(
+            match lage_target.type_of.as_deref() {
+                Some("noop") => "noop".into(),
+                Some("worker") => {
+                    self.report.approximated(
+                        file,
+                        format!("{pointer}/type"),
+                        "Worker targets are not supported, the package script is ran instead",
+                    );
+
+                    format!("{} run {}", self.package_manager, package_script)
+                }
+                _ => format!("{} run {}", self.package_manager, package_script),
+            },
+        ));
+
+        // Dependencies
+        if let Some(depends_on) = &lage_target.depends_on {
+            let mut deps = vec![];
+
+            for dep in depends_on {
+                // ^^task
+                let dep = match dep.strip_prefix('^') {
+                    Some(transitive_dep) if transitive_dep.starts_with('^') => {
+                        self.report.approximated(
+                            file,
+                            format!("{pointer}/dependsOn"),
+                            "Transitive dependencies were converted to direct dependencies",
+                        );
+
+                        transitive_dep
+                    }
+                    _ => dep,
+                };
+
+                match self.packages.parse_dependency(dep)? {
+                    PipelineDependency::EnvVar(env) => {
+                        inputs.push(InputPath::EnvVar(env));
+                    }
+                    PipelineDependency::Target(target) => {
+                        deps.push(PartialTaskDependency::Target(target));
+                    }
+                };
+            }
+
+            if !deps.is_empty() {
+                config.deps = Some(deps);
+            }
+        }
+
+        // Inputs
+        if let Some(raw_inputs) = &lage_target.inputs {
+            for input in raw_inputs {
+                inputs.push(InputPath::from_str(input)?);
+            }
+        }
+
+        if !inputs.is_empty() {
+            config.inputs = Some(inputs);
+        }
+
+        // Outputs, where no-op targets never produce any
+        let raw_outputs = match &lage_target.outputs {
+            Some(outputs) => outputs,
+            None if lage_target.type_of.as_deref() == Some("noop") => &vec![],
+            None => &self.output_globs,
+        };
+        let mut outputs = vec![];
+
+        for output in raw_outputs {
+            outputs.push(OutputPath::from_str(output)?);
+        }
+
+        if !outputs.is_empty() {
+            config.outputs = Some(outputs);
+        }
+
+        // Options
+        if lage_target.cache == Some(false) {
+            config
+                .options
+                .get_or_insert(PartialTaskOptionsConfig::default())
+                .cache = Some(false);
+        }
+
+        if lage_target.priority.is_some() {
+            self.report.unsupported(
+                file,
+                format!("{pointer}/priority"),
+                "Task priorities are not supported, tasks are scheduled by their dependencies",
+            );
+        }
+
+        for key in lage_target.unknown.keys() {
+            self.report.unsupported(
+                file,
+                format!("{pointer}{}", create_pointer([key])),
+                "Setting is not supported",
+            );
+        }
+
+        Ok(config)
+    }
+}
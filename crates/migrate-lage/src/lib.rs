@@ -0,0 +1,7 @@
+mod lage_config;
+mod lage_migrator;
+#[cfg(feature = "wasm")]
+mod migrate_lage_ext;
+
+#[cfg(feature = "wasm")]
+pub use migrate_lage_ext::*;
@@ -0,0 +1,83 @@
+use crate::lage_migrator::LageMigrator;
+use extism_pdk::*;
+use moon_extension_common::migrator::{run_migration, TaskConflictStrategy};
+use moon_pdk::*;
+use starbase_utils::json;
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
+
+#[plugin_fn]
+pub fn register_extension(
+    Json(_): Json<ExtensionMetadataInput>,
+) -> FnResult<Json<ExtensionMetadataOutput>> {
+    Ok(Json(ExtensionMetadataOutput {
+        name: "Migrate Lage".into(),
+        description: Some("Migrate a Lage repository to moon by converting a JSON-serialized <file>lage.config.js</file> into moon configuration files.".into()),
+        plugin_version: env!("CARGO_PKG_VERSION").into(),
+        config_schema: None,
+    }))
+}
+
+#[derive(Args)]
+pub struct MigrateLageExtensionArgs {
+    #[arg(long)]
+    pub bun: bool,
+
+    #[arg(long)]
+    pub config: Option<String>,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
+}
+
+#[plugin_fn]
+pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
+    let args = parse_args::<MigrateLageExtensionArgs>(&input.args)?;
+    let workspace_root = &input.context.workspace_root;
+
+    run_migration(workspace_root, "Lage", args.dry_run, args.restore, || {
+        let mut migrator = LageMigrator::new(&input.context, args.bun)?;
+
+        // Lage is configured with JavaScript, which can't be evaluated here,
+        // so a JSON-serialized version of the config must be provided
+        let config_file = args.config.as_deref().unwrap_or("lage.config.json");
+        let config_path = workspace_root.join(config_file);
+
+        if !config_path.exists() {
+            return Err(if workspace_root.join("lage.config.js").exists() {
+                plugin_err!(
+                    "Unable to migrate, <file>lage.config.js</file> must be serialized to JSON first. Run <shell>node -e \"console.log(JSON.stringify(require('./lage.config.js')))\" > lage.config.json</shell> and try again."
+                )
+            } else {
+                plugin_err!(
+                    "Unable to migrate, no <file>{config_file}</file> found in the workspace root."
+                )
+            });
+        }
+
+        host_log!(stdout, "Migrating config <file>{}</file>", config_file);
+
+        migrator.migrate_config(json::read_file(&config_path)?, config_file)?;
+        migrator.inner.remove_file(config_path);
+
+        let js_config_path = workspace_root.join("lage.config.js");
+
+        if js_config_path.exists() {
+            migrator.inner.remove_file(js_config_path);
+        }
+
+        // Resolve migrated tasks that collide with existing moon tasks
+        migrator.inner.resolve_task_conflicts(args.on_conflict)?;
+
+        Ok((migrator.inner, migrator.report))
+    })
+}
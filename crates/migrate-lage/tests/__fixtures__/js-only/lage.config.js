@@ -0,0 +1,5 @@
+module.exports = {
+  pipeline: {
+    build: ['^build'],
+  },
+};
@@ -0,0 +1 @@
+module.exports = require('./lage.config.json');
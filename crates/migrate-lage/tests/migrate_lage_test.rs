@@ -0,0 +1,158 @@
+use moon_pdk_test_utils::{create_extension, ExecuteExtensionInput};
+use starbase_sandbox::{assert_snapshot, create_sandbox};
+use std::fs;
+
+mod migrate_lage {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_pipeline() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("lage.config.json").exists());
+        assert!(!sandbox.path().join("lage.config.js").exists());
+        assert!(sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(sandbox.path().join(".moon/toolchain.yml").exists());
+        assert!(sandbox.path().join("packages/client/moon.yml").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/toolchain.yml")).unwrap());
+        assert_snapshot!(
+            fs::read_to_string(sandbox.path().join("packages/client/moon.yml")).unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_force_bun_instead_of_node() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--bun".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join(".moon/toolchain.yml").exists());
+        assert!(sandbox.path().join(".moon/tasks/bun.yml").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/bun.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_use_a_custom_config_path() {
+        let sandbox = create_sandbox("custom-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--config".into(), "config/lage.json".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("config/lage.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_a_report_of_unsupported_settings() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_files_in_dry_run() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("lage.config.json").exists());
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_restore_from_backup() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("lage.config.json").exists());
+        assert!(sandbox.path().join("lage.config.js").exists());
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join(".moon/toolchain.yml").exists());
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "lage.config.js must be serialized to JSON first.")]
+    async fn errors_if_config_is_not_serialized() {
+        let sandbox = create_sandbox("js-only");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(
+        expected = "Unable to migrate, no lage.config.json found in the workspace root."
+    )]
+    async fn errors_if_no_config() {
+        let sandbox = create_sandbox("missing-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+}
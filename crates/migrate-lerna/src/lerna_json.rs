@@ -0,0 +1,43 @@
+// https://lerna.js.org/docs/api-reference/configuration
+// https://github.com/lerna/lerna/blob/main/packages/lerna/schemas/lerna-schema.json
+
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use starbase_utils::json::JsonValue;
+use std::collections::BTreeMap;
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LernaRunCommand {
+    pub npm_client: Option<String>,
+    pub parallel: Option<bool>,
+    pub sort: Option<bool>,
+    pub stream: Option<bool>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LernaCommands {
+    pub run: Option<LernaRunCommand>,
+    // Not supported:
+    // publish, version, bootstrap, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LernaJson {
+    pub command: Option<LernaCommands>,
+    pub npm_client: Option<String>,
+    pub packages: Option<Vec<String>>,
+    pub use_workspaces: Option<bool>,
+    pub version: Option<String>,
+    // Not supported:
+    // useNx, ignoreChanges, changelogPreset, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
@@ -0,0 +1,255 @@
+use crate::lerna_json::*;
+use moon_config::{
+    PartialTaskArgs, PartialTaskConfig, PartialTaskDependency, PartialWorkspaceProjects,
+    PlatformType,
+};
+use moon_extension_common::migrator::*;
+use moon_extension_common::package_json::*;
+use moon_extension_common::report::*;
+use moon_pdk::{map_miette_error, AnyResult, MoonContext};
+use moon_target::Target;
+use std::collections::BTreeMap;
+
+// Options of `lerna run` that are followed by a value.
+const OPTIONS_WITH_VALUE: &[&str] = &[
+    "--concurrency",
+    "--ignore",
+    "--loglevel",
+    "--npm-client",
+    "--profile-location",
+    "--scope",
+    "--since",
+];
+
+pub struct LernaMigrator {
+    pub inner: Migrator,
+    pub package_globs: Vec<String>,
+    pub package_manager: String,
+    pub report: MigrationReport,
+    /// Scripts that are ran with `lerna run`, and whether they
+    /// are ran in topological order or not.
+    pub run_scripts: BTreeMap<String, bool>,
+}
+
+impl LernaMigrator {
+    pub fn new(context: &MoonContext, bun: bool) -> AnyResult<Self> {
+        let mut migrator = Migrator::new(&context.workspace_root)?;
+
+        if bun {
+            migrator.platform = PlatformType::Bun;
+        }
+
+        Ok(Self {
+            package_globs: vec![],
+            package_manager: migrator.detect_package_manager(),
+            inner: migrator,
+            report: MigrationReport::default(),
+            run_scripts: BTreeMap::default(),
+        })
+    }
+
+    pub fn migrate_root_config(
+        &mut self,
+        lerna_json: LernaJson,
+        package_json: PackageJson,
+    ) -> AnyResult<()> {
+        let file = "lerna.json";
+
+        for key in lerna_json.unknown.keys() {
+            let reason = match key.as_str() {
+                "$schema" => continue,
+                "ignoreChanges" => "Use task inputs to control affected files instead",
+                "useNx" => "Nx task runner is not supported, tasks are ran by moon",
+                _ => "Setting is not supported",
+            };
+
+            self.report.unsupported(file, create_pointer([key]), reason);
+        }
+
+        if lerna_json.version.is_some() {
+            self.report.unsupported(
+                file,
+                "/version",
+                "Versioning and publishing packages is not supported",
+            );
+        }
+
+        // Package manager
+        let run_command = lerna_json
+            .command
+            .as_ref()
+            .and_then(|command| command.run.as_ref());
+
+        if let Some(npm_client) = run_command
+            .and_then(|run| run.npm_client.as_ref())
+            .or(lerna_json.npm_client.as_ref())
+        {
+            // Bun takes precedence when forced
+            if !matches!(self.inner.platform, PlatformType::Bun) {
+                self.package_manager = npm_client.to_owned();
+            }
+        }
+
+        // Packages
+        let workspaces = package_json
+            .workspaces
+            .as_ref()
+            .map(|workspaces| workspaces.get_globs().to_vec());
+
+        self.package_globs = match (workspaces, lerna_json.packages) {
+            // Lerna < v7 requires opting into the package manager's workspaces
+            (Some(workspaces), _) if lerna_json.use_workspaces == Some(true) => workspaces,
+            (_, Some(packages)) => packages,
+            (Some(workspaces), None) => workspaces,
+            (None, None) => vec!["packages/*".into()],
+        };
+
+        let workspace_config = self.inner.load_workspace_config()?;
+
+        if workspace_config.projects.is_none() {
+            workspace_config.projects =
+                Some(PartialWorkspaceProjects::Globs(self.package_globs.clone()));
+        }
+
+        // Run command defaults
+        let mut topological = true;
+
+        if let Some(command) = &lerna_json.command {
+            for key in command.unknown.keys() {
+                self.report.unsupported(
+                    file,
+                    create_pointer(["command", key]),
+                    "Only the run command is supported",
+                );
+            }
+        }
+
+        if let Some(run) = run_command {
+            if run.parallel == Some(true) || run.sort == Some(false) {
+                topological = false;
+            }
+
+            for key in run.unknown.keys() {
+                self.report.unsupported(
+                    file,
+                    create_pointer(["command", "run", key]),
+                    "Run option is not supported",
+                );
+            }
+        }
+
+        // Scripts that are orchestrated from the root
+        if let Some(scripts) = &package_json.scripts {
+            for (name, script) in scripts {
+                self.extract_run_scripts(name, script, topological);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn migrate_package(
+        &mut self,
+        project_source: &str,
+        package_json: PackageJson,
+    ) -> AnyResult<()> {
+        let Some(scripts) = package_json.scripts else {
+            return Ok(());
+        };
+
+        let mut tasks = BTreeMap::default();
+
+        for name in scripts.keys() {
+            if LIFECYCLE_SCRIPTS.contains(&name.as_str()) {
+                continue;
+            }
+
+            let task_id = create_id(name)?;
+            let mut task = PartialTaskConfig {
+                command: Some(PartialTaskArgs::String(format!(
+                    "{} run {name}",
+                    self.package_manager
+                ))),
+                ..PartialTaskConfig::default()
+            };
+
+            // `lerna run` waits for dependencies to complete first
+            if self.run_scripts.get(name).is_some_and(|topo| *topo) {
+                task.deps = Some(vec![PartialTaskDependency::Target(
+                    Target::parse(format!("^:{task_id}").as_str()).map_err(map_miette_error)?,
+                )]);
+            }
+
+            tasks.insert(task_id, task);
+        }
+
+        if !tasks.is_empty() {
+            self.inner
+                .load_project_config(project_source)?
+                .tasks
+                .get_or_insert(BTreeMap::default())
+                .extend(tasks);
+        }
+
+        Ok(())
+    }
+
+    // https://lerna.js.org/docs/api-reference/commands#lerna-run
+    fn extract_run_scripts(&mut self, name: &str, script: &str, topological_default: bool) {
+        for command in script.split(['&', '|', ';']) {
+            let args = command.split_whitespace().collect::<Vec<_>>();
+
+            let Some(index) = args
+                .windows(2)
+                .position(|pair| pair[0] == "lerna" && pair[1] == "run")
+            else {
+                continue;
+            };
+
+            let mut topological = topological_default;
+            let mut run_scripts = vec![];
+            let mut args = args[index + 2..].iter().peekable();
+
+            while let Some(arg) = args.next() {
+                match *arg {
+                    "--parallel" | "--no-sort" => {
+                        topological = false;
+                    }
+                    "--sort" | "--stream" => {
+                        // moon streams output and sorts by default
+                    }
+                    // Arguments passed to the scripts themselves
+                    "--" => {
+                        break;
+                    }
+                    option if option.starts_with('-') => {
+                        // Skip the value of the option, so that it's not
+                        // mistaken for the script name
+                        if OPTIONS_WITH_VALUE.contains(&option)
+                            && args.peek().is_some_and(|value| !value.starts_with('-'))
+                        {
+                            args.next();
+                        }
+
+                        self.report.unsupported(
+                            "package.json",
+                            create_pointer(["scripts", name]),
+                            format!("Option {option} of lerna run is not supported"),
+                        );
+                    }
+                    value => {
+                        if run_scripts.is_empty() {
+                            run_scripts.extend(value.split(',').map(|s| s.to_owned()));
+                        }
+                    }
+                };
+            }
+
+            for run_script in run_scripts {
+                let entry = self.run_scripts.entry(run_script).or_default();
+
+                *entry = *entry || topological;
+            }
+        }
+    }
+}
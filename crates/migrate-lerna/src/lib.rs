@@ -0,0 +1,7 @@
+mod lerna_json;
+mod lerna_migrator;
+#[cfg(feature = "wasm")]
+mod migrate_lerna_ext;
+
+#[cfg(feature = "wasm")]
+pub use migrate_lerna_ext::*;
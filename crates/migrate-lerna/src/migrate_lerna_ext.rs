@@ -0,0 +1,96 @@
+use crate::lerna_migrator::LernaMigrator;
+use extism_pdk::*;
+use moon_extension_common::migrator::{run_migration, TaskConflictStrategy};
+use moon_extension_common::package_json::{create_package_json_globs, PackageJson};
+use moon_pdk::*;
+use starbase_utils::{glob, json};
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
+
+#[plugin_fn]
+pub fn register_extension(
+    Json(_): Json<ExtensionMetadataInput>,
+) -> FnResult<Json<ExtensionMetadataOutput>> {
+    Ok(Json(ExtensionMetadataOutput {
+        name: "Migrate Lerna".into(),
+        description: Some("Migrate a Lerna repository to moon by converting <file>lerna.json</file> and package scripts into moon configuration files.".into()),
+        plugin_version: env!("CARGO_PKG_VERSION").into(),
+        config_schema: None,
+    }))
+}
+
+#[derive(Args)]
+pub struct MigrateLernaExtensionArgs {
+    #[arg(long)]
+    pub bun: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
+}
+
+#[plugin_fn]
+pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
+    let args = parse_args::<MigrateLernaExtensionArgs>(&input.args)?;
+    let workspace_root = &input.context.workspace_root;
+
+    run_migration(workspace_root, "Lerna", args.dry_run, args.restore, || {
+        let mut migrator = LernaMigrator::new(&input.context, args.bun)?;
+
+        // Migrate the root config first, so we can find packages
+        let root_config_path = workspace_root.join("lerna.json");
+
+        if !root_config_path.exists() {
+            return Err(plugin_err!(
+                "Unable to migrate, no <file>lerna.json</file> found in the workspace root."
+            ));
+        }
+
+        let root_package_path = workspace_root.join("package.json");
+        let root_package_json: PackageJson = if root_package_path.exists() {
+            json::read_file(&root_package_path)?
+        } else {
+            PackageJson::default()
+        };
+
+        host_log!(stdout, "Migrating root config <file>lerna.json</file>");
+
+        migrator.migrate_root_config(json::read_file(&root_config_path)?, root_package_json)?;
+        migrator.inner.remove_file(root_config_path);
+
+        // Then convert the scripts of each package
+        for package_json_path in glob::walk_files(
+            workspace_root,
+            &create_package_json_globs(&migrator.package_globs),
+        )? {
+            let rel_package_path = package_json_path.strip_prefix(workspace_root).unwrap();
+            let project_source = rel_package_path.parent().unwrap().to_string_lossy();
+
+            // The root package is not a project
+            if project_source.is_empty() {
+                continue;
+            }
+
+            host_log!(
+                stdout,
+                "Migrating package scripts <file>{}</file>",
+                rel_package_path.display()
+            );
+
+            migrator.migrate_package(&project_source, json::read_file(&package_json_path)?)?;
+        }
+
+        // Resolve migrated tasks that collide with existing moon tasks
+        migrator.inner.resolve_task_conflicts(args.on_conflict)?;
+
+        Ok((migrator.inner, migrator.report))
+    })
+}
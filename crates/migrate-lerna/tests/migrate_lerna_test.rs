@@ -0,0 +1,155 @@
+use moon_pdk_test_utils::{create_extension, ExecuteExtensionInput};
+use starbase_sandbox::{assert_snapshot, create_sandbox};
+use std::fs;
+
+mod migrate_lerna {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_package_scripts() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("lerna.json").exists());
+        assert!(sandbox.path().join(".moon/workspace.yml").exists());
+        assert!(sandbox.path().join("packages/a/moon.yml").exists());
+        assert!(sandbox.path().join("packages/b/moon.yml").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/b/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn uses_package_manager_workspaces() {
+        let sandbox = create_sandbox("workspaces");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("lerna.json").exists());
+        assert!(sandbox.path().join("packages/a/moon.yml").exists());
+        assert!(sandbox.path().join("packages/b/moon.yml").exists());
+        assert!(!sandbox.path().join("packages/internal/moon.yml").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/b/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn skips_values_of_run_options() {
+        let sandbox = create_sandbox("run-options");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_force_bun_instead_of_node() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--bun".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_a_report_of_unsupported_settings() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_files_in_dry_run() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("lerna.json").exists());
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+        assert!(!sandbox.path().join("packages/a/moon.yml").exists());
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_restore_from_backup() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("lerna.json").exists());
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+        assert!(!sandbox.path().join("packages/a/moon.yml").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "Unable to migrate, no lerna.json found in the workspace root.")]
+    async fn errors_if_no_config() {
+        let sandbox = create_sandbox("missing-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+}
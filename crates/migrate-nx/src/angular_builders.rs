@@ -0,0 +1,127 @@
+// https://angular.dev/reference/configs/workspace-config#configuring-builder-targets
+
+use crate::nx_executors::to_source_path;
+use crate::nx_json::NxTargetOptions;
+use moon_config::{InputPath, OutputPath, PartialTaskArgs, PartialTaskConfig};
+use moon_pdk::AnyResult;
+use starbase_utils::json::JsonValue;
+use std::str::FromStr;
+
+pub struct NgBuilder {
+    /// Long-running processes, like development servers.
+    pub local: bool,
+    /// Options with workspace relative files that are inputs of the task.
+    pub config_files: &'static [&'static str],
+    /// Option with the workspace relative output directory.
+    pub output_path: Option<&'static str>,
+}
+
+pub fn find_angular_builder(builder: &str) -> Option<NgBuilder> {
+    // Builders were moved to @angular/build in v18
+    let name = builder
+        .strip_prefix("@angular-devkit/build-angular:")
+        .or_else(|| builder.strip_prefix("@angular/build:"))?;
+
+    Some(match name {
+        "application" | "browser" | "browser-esbuild" | "server" => NgBuilder {
+            local: false,
+            config_files: &["tsConfig"],
+            output_path: Some("outputPath"),
+        },
+        "dev-server" | "ssr-dev-server" => NgBuilder {
+            local: true,
+            config_files: &[],
+            output_path: None,
+        },
+        "extract-i18n" => NgBuilder {
+            local: false,
+            config_files: &[],
+            output_path: Some("outputPath"),
+        },
+        "karma" => NgBuilder {
+            local: false,
+            config_files: &["karmaConfig", "tsConfig"],
+            output_path: None,
+        },
+        "jest" | "web-test-runner" => NgBuilder {
+            local: false,
+            config_files: &["tsConfig"],
+            output_path: None,
+        },
+        "ng-packagr" => NgBuilder {
+            local: false,
+            config_files: &["project", "tsConfig"],
+            output_path: None,
+        },
+        _ => return None,
+    })
+}
+
+/// Builders read their options from `angular.json`, so the task runs the
+/// target through the Angular CLI, and only inputs and outputs are derived
+/// from the options.
+pub fn migrate_angular_task(
+    project_name: &str,
+    target_name: &str,
+    ng_target: &NxTargetOptions,
+) -> AnyResult<PartialTaskConfig> {
+    let mut config = PartialTaskConfig {
+        command: Some(PartialTaskArgs::String("ng".into())),
+        args: Some(PartialTaskArgs::List(vec![
+            "run".into(),
+            format!("{project_name}:{target_name}"),
+        ])),
+        ..PartialTaskConfig::default()
+    };
+
+    let Some(builder) = ng_target.executor.as_deref().and_then(find_angular_builder) else {
+        return Ok(config);
+    };
+
+    let get_option = |name: &str| ng_target.options.as_ref().and_then(|map| map.get(name));
+
+    // Config files are inputs in addition to the project's files
+    let mut inputs = vec![];
+
+    for name in builder.config_files {
+        if let Some(JsonValue::String(path)) = get_option(name) {
+            inputs.push(InputPath::from_str(&to_source_path(path))?);
+        }
+    }
+
+    if !inputs.is_empty() {
+        inputs.push(InputPath::ProjectGlob("**/*".into()));
+
+        config.inputs = Some(inputs);
+    }
+
+    if let Some(name) = builder.output_path {
+        // The application builder also accepts an object with a base directory
+        let output_path = match get_option(name) {
+            Some(JsonValue::String(path)) => Some(path),
+            Some(JsonValue::Object(map)) => match map.get("base") {
+                Some(JsonValue::String(path)) => Some(path),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(path) = output_path {
+            let output = to_source_path(path);
+
+            // Outputs must be within the workspace
+            if !output.contains("..") {
+                config.outputs = Some(vec![OutputPath::from_str(&format!(
+                    "{}/**/*",
+                    output.trim_end_matches('/')
+                ))?]);
+            }
+        }
+    }
+
+    if builder.local {
+        config.local = Some(true);
+    }
+
+    Ok(config)
+}
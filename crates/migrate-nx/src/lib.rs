@@ -1,7 +1,10 @@
+mod angular_builders;
 #[cfg(feature = "wasm")]
 mod migrate_nx_ext;
+mod nx_executors;
 mod nx_json;
 mod nx_migrator;
+mod nx_plugins;
 mod nx_project_json;
 
 #[cfg(feature = "wasm")]
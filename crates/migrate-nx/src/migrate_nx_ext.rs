@@ -1,5 +1,7 @@
 use crate::nx_migrator::NxMigrator;
 use extism_pdk::*;
+use moon_extension_common::migrator::{create_id, run_migration, TaskConflictStrategy};
+use moon_extension_common::project_graph::ProjectGraph;
 use moon_pdk::*;
 use starbase_utils::{fs, glob, json};
 
@@ -24,85 +26,144 @@ pub fn register_extension(
 pub struct MigrateNxExtensionArgs {
     #[arg(long)]
     pub bun: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
 }
 
 #[plugin_fn]
 pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
     let args = parse_args::<MigrateNxExtensionArgs>(&input.args)?;
     let workspace_root = &input.context.workspace_root;
-    let mut migrator = NxMigrator::new(&input.context, args.bun)?;
-
-    // Migrate the workspace config first, so we can handle projects
-    let workspace_config_path = workspace_root.join("workspace.json");
-
-    if workspace_config_path.exists() {
-        host_log!(
-            stdout,
-            "Migrating workspace config <file>workspace.json</file>",
-        );
-
-        migrator.migrate_workspace_config(json::read_file(&workspace_config_path)?)?;
-
-        fs::remove(workspace_config_path)?;
-    }
-
-    // Then the root nx config second, to handle project defaults
-    let root_config_path = workspace_root.join("nx.json");
-
-    if root_config_path.exists() {
-        host_log!(stdout, "Migrating root config <file>nx.json</file>",);
-
-        migrator.migrate_root_config(json::read_file(&root_config_path)?)?;
-
-        fs::remove(root_config_path)?;
-    }
-
-    // And lastly, all project configs (and package.json to)
-    for project_config_path in glob::walk_files(
-        workspace_root,
-        [
-            "**/*/package.json",
-            "**/*/project.json",
-            "!**/node_modules/**/*",
-            // The globstar above won't find the root files for some reason...
-            "package.json",
-            "project.json",
-        ],
-    )? {
-        let rel_config_path = project_config_path.strip_prefix(workspace_root).unwrap();
-        let project_source = rel_config_path.parent().unwrap().to_string_lossy();
-
-        host_log!(
-            stdout,
-            "Migrating project config <file>{}</file>",
-            rel_config_path.display()
-        );
-
-        if project_config_path
-            .file_name()
-            .is_some_and(|name| name == "package.json")
-        {
-            migrator.migrate_project_package_config(
-                &project_source,
-                json::read_file(&project_config_path)?,
+
+    run_migration(workspace_root, "Nx", args.dry_run, args.restore, || {
+        let mut migrator = NxMigrator::new(&input.context, args.bun)?;
+
+        // Migrate the root nx config first, to handle project defaults
+        let root_config_path = workspace_root.join("nx.json");
+
+        if root_config_path.exists() {
+            host_log!(stdout, "Migrating root config <file>nx.json</file>",);
+
+            migrator.migrate_root_config(json::read_file(&root_config_path)?)?;
+
+            migrator.inner.remove_file(root_config_path);
+        }
+
+        // Then the workspace config second, which may define projects inline
+        for workspace_config_name in ["workspace.json", "angular.json"] {
+            let workspace_config_path = workspace_root.join(workspace_config_name);
+
+            if !workspace_config_path.exists() {
+                continue;
+            }
+
+            host_log!(
+                stdout,
+                "Migrating workspace config <file>{}</file>",
+                workspace_config_name
+            );
+
+            // Without Nx, builders are ran through the Angular CLI,
+            // which requires angular.json, so don't delete it
+            migrator.angular_cli =
+                workspace_config_name == "angular.json" && !workspace_root.join("nx.json").exists();
+
+            migrator.migrate_workspace_config(
+                workspace_config_name,
+                json::read_file(&workspace_config_path)?,
             )?;
 
-            // Don't delete package.json
-        } else {
-            migrator
-                .migrate_project_config(&project_source, json::read_file(&project_config_path)?)?;
+            if !migrator.angular_cli {
+                migrator.inner.remove_file(workspace_config_path);
+            }
+
+            break;
+        }
+
+        // And lastly, all project configs (and package.json to)
+        for project_config_path in glob::walk_files(
+            workspace_root,
+            [
+                "**/*/package.json",
+                "**/*/project.json",
+                "!**/node_modules/**/*",
+                // The globstar above won't find the root files for some reason...
+                "package.json",
+                "project.json",
+            ],
+        )? {
+            let rel_config_path = project_config_path.strip_prefix(workspace_root).unwrap();
+            let project_source = rel_config_path.parent().unwrap().to_string_lossy();
+
+            host_log!(
+                stdout,
+                "Migrating project config <file>{}</file>",
+                rel_config_path.display()
+            );
+
+            if project_config_path
+                .file_name()
+                .is_some_and(|name| name == "package.json")
+            {
+                migrator.migrate_project_package_config(
+                    &project_source,
+                    json::read_file(&project_config_path)?,
+                )?;
+
+                // Don't delete package.json
+            } else {
+                migrator.migrate_project_config(
+                    &project_source,
+                    json::read_file(&project_config_path)?,
+                )?;
+
+                migrator
+                    .inner
+                    .remove_file(workspace_root.join(rel_config_path));
+            }
+        }
+
+        // Resolve implicit dependencies now that all project names are known
+        migrator.migrate_implicit_dependencies()?;
 
-            fs::remove(project_config_path)?;
+        // Infer tasks from plugins for each project
+        for project_source in migrator.project_sources.clone() {
+            migrator.migrate_inferred_tasks(&project_source)?;
         }
-    }
 
-    // Fill in any missing but required settings
-    migrator.use_default_settings()?;
+        // Link configuration tasks now that all projects are known
+        migrator.migrate_configuration_deps()?;
 
-    // Write the new config files
-    migrator.inner.save_configs()?;
+        // Resolve inputs of dependency projects now that all tasks are known
+        migrator.migrate_dependency_inputs()?;
+
+        // Fill in any missing but required settings
+        migrator.use_default_settings()?;
+
+        // Resolve migrated tasks that collide with existing moon tasks
+        migrator.inner.resolve_task_conflicts(args.on_conflict)?;
+
+        // Ensure all task targets and dependencies point to known projects
+        let mut project_graph = ProjectGraph::load_with_migrated(&migrator.inner)?;
+
+        for project_source in &migrator.project_sources {
+            let id = match migrator.project_names.get(project_source) {
+                Some(name) => create_id(name)?.to_string(),
+                None => fs::file_name(workspace_root.join(project_source)),
+            };
+
+            project_graph.add_project(id, project_source, None);
+        }
 
-    host_log!(stdout, "Successfully migrated from Nx to moon!");
+        project_graph.validate_references(&migrator.inner)?;
 
-    Ok(())
+        Ok((migrator.inner, migrator.report))
+    })
 }
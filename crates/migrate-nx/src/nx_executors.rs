@@ -0,0 +1,504 @@
+// https://nx.dev/nx-api
+
+use crate::nx_migrator::{convert_value_to_string_without_quotes, replace_tokens};
+use moon_config::{InputPath, OutputPath};
+use moon_extension_common::report::{create_pointer, MigrationReport};
+use moon_pdk::AnyResult;
+use rustc_hash::FxHashMap;
+use starbase_utils::json::JsonValue;
+use std::str::FromStr;
+
+/// How an executor option is passed to the native command.
+pub enum NxExecutorArg {
+    /// Passed as a flag followed by the value, or joined with the value
+    /// when the flag ends with `=`. Booleans only pass the flag when true.
+    Flag(&'static str),
+    /// Like `Flag`, but enabled by the native command by default,
+    /// so false booleans are passed as `--flag=false`.
+    EnabledFlag(&'static str),
+    /// Passed as a flag with a workspace relative path.
+    PathFlag(&'static str),
+    /// Passed as a flag (optional) with a workspace relative directory,
+    /// that is also an output of the task.
+    OutputDir(Option<&'static str>),
+    /// Passed as a flag with a workspace relative file,
+    /// that is also an input of the task.
+    ConfigFile(&'static str),
+    /// Passed as a workspace relative path after the flags.
+    Positional,
+    /// Passed as the workspace relative directory of a file after the flags,
+    /// or the project root (reported) when the option isn't defined.
+    SourceDir,
+    /// Passed as a flag of the same name as the value, e.g. `--e2e`.
+    Switch,
+    /// Not required by the native command.
+    Ignored,
+}
+
+pub struct NxExecutor {
+    pub command: &'static str,
+    /// Values that the executor uses for options that aren't defined,
+    /// but that the native command doesn't default to.
+    pub defaults: &'static [(&'static str, &'static str)],
+    /// Passed after the flags when no positional option is defined.
+    pub default_positional: Option<&'static str>,
+    /// Long-running processes, like development servers.
+    pub local: bool,
+    pub options: &'static [(&'static str, NxExecutorArg)],
+    /// Outputs that are always created, relative to the project.
+    pub outputs: &'static [&'static str],
+}
+
+/// The result of converting executor options.
+#[derive(Default)]
+pub struct NxExecutorOptions {
+    pub args: Vec<String>,
+    pub inputs: Vec<InputPath>,
+    pub outputs: Vec<OutputPath>,
+}
+
+use NxExecutorArg::*;
+
+pub fn find_executor(executor: &str) -> Option<NxExecutor> {
+    // Packages were moved from the @nrwl scope in v16
+    let executor = match executor.strip_prefix("@nrwl/") {
+        Some(name) => format!("@nx/{name}"),
+        None => executor.to_owned(),
+    };
+
+    Some(match executor.as_str() {
+        // https://nx.dev/nx-api/js/executors/tsc
+        "@nx/js:tsc" => NxExecutor {
+            command: "tsc",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("tsConfig", ConfigFile("--project")),
+                ("outputPath", OutputDir(Some("--outDir"))),
+                ("rootDir", PathFlag("--rootDir")),
+                ("watch", Flag("--watch")),
+                ("main", Ignored),
+                ("assets", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/js/executors/swc
+        "@nx/js:swc" => NxExecutor {
+            command: "swc",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("swcrc", ConfigFile("--config-file")),
+                ("outputPath", OutputDir(Some("--out-dir"))),
+                ("sourceMap", Flag("--source-maps")),
+                ("stripLeadingPaths", Flag("--strip-leading-paths")),
+                ("watch", Flag("--watch")),
+                ("main", SourceDir),
+                ("tsConfig", Ignored),
+                ("assets", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/vite/executors/build
+        "@nx/vite:build" => NxExecutor {
+            command: "vite build",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("configFile", ConfigFile("--config")),
+                ("outputPath", OutputDir(Some("--outDir"))),
+                ("base", Flag("--base")),
+                ("emptyOutDir", EnabledFlag("--emptyOutDir")),
+                ("minify", EnabledFlag("--minify")),
+                ("mode", Flag("--mode")),
+                ("sourcemap", Flag("--sourcemap")),
+                ("watch", Flag("--watch")),
+                ("buildLibsFromSource", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/vite/executors/dev-server
+        "@nx/vite:dev-server" => NxExecutor {
+            command: "vite dev",
+            defaults: &[],
+            default_positional: None,
+            local: true,
+            options: &[
+                ("configFile", ConfigFile("--config")),
+                ("host", Flag("--host")),
+                ("https", Flag("--https")),
+                ("mode", Flag("--mode")),
+                ("open", Flag("--open")),
+                ("port", Flag("--port")),
+                ("buildLibsFromSource", Ignored),
+                ("buildTarget", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/vite/executors/test
+        "@nx/vite:test" => NxExecutor {
+            command: "vitest run",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("configFile", ConfigFile("--config")),
+                (
+                    "reportsDirectory",
+                    OutputDir(Some("--coverage.reportsDirectory")),
+                ),
+                ("coverage", Flag("--coverage")),
+                ("mode", Flag("--mode")),
+                ("passWithNoTests", Flag("--passWithNoTests")),
+                ("testNamePattern", Flag("--testNamePattern")),
+                ("update", Flag("--update")),
+                ("testFiles", Positional),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/jest/executors/jest
+        "@nx/jest:jest" => NxExecutor {
+            command: "jest",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("jestConfig", ConfigFile("--config")),
+                ("coverageDirectory", OutputDir(Some("--coverageDirectory"))),
+                ("ci", Flag("--ci")),
+                ("codeCoverage", Flag("--coverage")),
+                ("detectOpenHandles", Flag("--detectOpenHandles")),
+                ("maxWorkers", Flag("--maxWorkers")),
+                ("passWithNoTests", Flag("--passWithNoTests")),
+                ("runInBand", Flag("--runInBand")),
+                ("silent", Flag("--silent")),
+                ("testNamePattern", Flag("--testNamePattern")),
+                ("updateSnapshot", Flag("--updateSnapshot")),
+                ("watch", Flag("--watch")),
+                ("testFile", Positional),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/eslint/executors/lint
+        "@nx/eslint:lint" | "@nx/linter:eslint" => NxExecutor {
+            command: "eslint",
+            defaults: &[],
+            default_positional: Some("."),
+            local: false,
+            options: &[
+                ("eslintConfig", ConfigFile("--config")),
+                ("cache", Flag("--cache")),
+                ("cacheLocation", PathFlag("--cache-location")),
+                ("fix", Flag("--fix")),
+                ("format", Flag("--format")),
+                ("ignorePath", PathFlag("--ignore-path")),
+                ("maxWarnings", Flag("--max-warnings")),
+                ("outputFile", PathFlag("--output-file")),
+                ("quiet", Flag("--quiet")),
+                ("lintFilePatterns", Positional),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/webpack/executors/webpack
+        "@nx/webpack:webpack" => NxExecutor {
+            command: "webpack build",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("webpackConfig", ConfigFile("--config")),
+                ("outputPath", OutputDir(Some("--output-path"))),
+                ("main", PathFlag("--entry")),
+                ("watch", Flag("--watch")),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/webpack/executors/dev-server
+        "@nx/webpack:dev-server" => NxExecutor {
+            command: "webpack serve",
+            defaults: &[],
+            default_positional: None,
+            local: true,
+            options: &[
+                ("host", Flag("--host")),
+                ("open", Flag("--open")),
+                ("port", Flag("--port")),
+                ("buildTarget", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/esbuild/executors/esbuild
+        "@nx/esbuild:esbuild" => NxExecutor {
+            command: "esbuild",
+            defaults: &[("bundle", "true"), ("format", "esm"), ("platform", "node")],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("tsConfig", ConfigFile("--tsconfig=")),
+                ("outputPath", OutputDir(Some("--outdir="))),
+                ("bundle", Flag("--bundle")),
+                ("format", Flag("--format=")),
+                ("minify", Flag("--minify")),
+                ("platform", Flag("--platform=")),
+                ("sourcemap", Flag("--sourcemap")),
+                ("target", Flag("--target=")),
+                ("watch", Flag("--watch")),
+                ("main", Positional),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/next/executors/build
+        "@nx/next:build" => NxExecutor {
+            command: "next build",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("debug", Flag("--debug")),
+                ("profile", Flag("--profile")),
+                ("outputPath", OutputDir(None)),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/next/executors/server
+        "@nx/next:server" => NxExecutor {
+            command: "next dev",
+            defaults: &[],
+            default_positional: None,
+            local: true,
+            options: &[
+                ("hostname", Flag("--hostname")),
+                ("port", Flag("--port")),
+                ("turbo", Flag("--turbo")),
+                ("buildTarget", Ignored),
+                ("dev", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/playwright/executors/playwright
+        "@nx/playwright:playwright" => NxExecutor {
+            command: "playwright test",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("config", ConfigFile("--config")),
+                ("output", OutputDir(Some("--output"))),
+                ("grep", Flag("--grep")),
+                ("headed", Flag("--headed")),
+                ("project", Flag("--project")),
+                ("reporter", Flag("--reporter")),
+                ("retries", Flag("--retries")),
+                ("workers", Flag("--workers")),
+                ("skipInstall", Ignored),
+            ],
+            outputs: &[],
+        },
+        // https://nx.dev/nx-api/cypress/executors/cypress
+        "@nx/cypress:cypress" => NxExecutor {
+            command: "cypress run",
+            defaults: &[],
+            default_positional: None,
+            local: false,
+            options: &[
+                ("cypressConfig", ConfigFile("--config-file")),
+                ("browser", Flag("--browser")),
+                ("headed", Flag("--headed")),
+                ("key", Flag("--key")),
+                ("parallel", Flag("--parallel")),
+                ("record", Flag("--record")),
+                ("spec", Flag("--spec")),
+                ("testingType", Switch),
+            ],
+            outputs: &[],
+        },
+        _ => return None,
+    })
+}
+
+// Nx paths are relative to the workspace root, unless they use a token
+fn to_workspace_path(value: &str) -> String {
+    let value = replace_tokens(value, false);
+
+    if value.starts_with('$') {
+        value
+    } else {
+        format!("$workspaceRoot/{}", value.trim_start_matches('/'))
+    }
+}
+
+/// Convert a workspace relative path (unless it starts with a token)
+/// to a moon input or output path.
+pub fn to_source_path(value: &str) -> String {
+    if value.starts_with("{projectRoot}/") || value.starts_with("{workspaceRoot}/") {
+        replace_tokens(value, true)
+    } else {
+        format!(
+            "/{}",
+            replace_tokens(value, true)
+                .trim_start_matches("./")
+                .trim_start_matches('/')
+        )
+    }
+}
+
+fn push_flag(args: &mut Vec<String>, flag: &str, value: String) {
+    if flag.ends_with('=') {
+        args.push(format!("{flag}{value}"));
+    } else {
+        args.push(flag.to_owned());
+        args.push(value);
+    }
+}
+
+impl NxExecutor {
+    /// Options of configurations are appended to the options of the target,
+    /// so defaults are only applied when not migrating a configuration.
+    pub fn migrate_options(
+        &self,
+        options: &FxHashMap<String, JsonValue>,
+        for_configuration: bool,
+        report: &mut MigrationReport,
+        executor: &str,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<NxExecutorOptions> {
+        let mut result = NxExecutorOptions::default();
+        let mut positionals = vec![];
+        let mut options = options.to_owned();
+
+        for (name, value) in self.defaults.iter().filter(|_| !for_configuration) {
+            options
+                .entry((*name).to_owned())
+                .or_insert_with(|| match *value {
+                    "true" => JsonValue::Bool(true),
+                    "false" => JsonValue::Bool(false),
+                    value => JsonValue::String(value.to_owned()),
+                });
+        }
+
+        for (name, arg) in self.options {
+            let values = match options.get(*name) {
+                None | Some(JsonValue::Null) => {
+                    if matches!(arg, SourceDir) && !for_configuration {
+                        positionals.push(".".to_owned());
+
+                        report.approximated(
+                            file,
+                            pointer,
+                            format!(
+                                "The {name} option is not defined, so all files of the project are compiled"
+                            ),
+                        );
+                    }
+
+                    continue;
+                }
+                Some(JsonValue::Array(list)) => list.iter().collect::<Vec<_>>(),
+                Some(value) => vec![value],
+            };
+
+            for value in values {
+                if value.is_object() {
+                    report.unsupported(
+                        file,
+                        format!("{pointer}{}", create_pointer([*name])),
+                        format!("Object values cannot be passed to the {executor} executor"),
+                    );
+
+                    break;
+                }
+
+                let string = convert_value_to_string_without_quotes(value);
+
+                match arg {
+                    Flag(flag) | EnabledFlag(flag) => match value {
+                        JsonValue::Bool(true) => {
+                            result.args.push(flag.trim_end_matches('=').to_owned());
+                        }
+                        JsonValue::Bool(false) => {
+                            if matches!(arg, EnabledFlag(_)) {
+                                result
+                                    .args
+                                    .push(format!("{}=false", flag.trim_end_matches('=')));
+                            }
+                        }
+                        _ => push_flag(&mut result.args, flag, string),
+                    },
+                    PathFlag(flag) => {
+                        push_flag(&mut result.args, flag, to_workspace_path(&string));
+                    }
+                    OutputDir(flag) => {
+                        if let Some(flag) = flag {
+                            push_flag(&mut result.args, flag, to_workspace_path(&string));
+                        }
+
+                        let output = to_source_path(&string);
+
+                        // Outputs must be within the workspace
+                        if !output.contains("..") {
+                            result.outputs.push(OutputPath::from_str(&format!(
+                                "{}/**/*",
+                                output.trim_end_matches('/')
+                            ))?);
+                        }
+                    }
+                    ConfigFile(flag) => {
+                        push_flag(&mut result.args, flag, to_workspace_path(&string));
+
+                        result
+                            .inputs
+                            .push(InputPath::from_str(&to_source_path(&string))?);
+                    }
+                    Positional => {
+                        positionals.push(to_workspace_path(&string));
+                    }
+                    SourceDir => {
+                        let dir = match string.rsplit_once('/') {
+                            Some((dir, _)) => dir,
+                            None => ".",
+                        };
+
+                        positionals.push(to_workspace_path(dir));
+                    }
+                    Switch => {
+                        result.args.push(format!("--{string}"));
+                    }
+                    Ignored => {}
+                };
+            }
+        }
+
+        if positionals.is_empty() && !for_configuration {
+            if let Some(positional) = self.default_positional {
+                positionals.push(positional.to_owned());
+            }
+        }
+
+        result.args.extend(positionals);
+
+        for output in self.outputs {
+            result.outputs.push(OutputPath::from_str(output)?);
+        }
+
+        let mut unknown_options = options
+            .keys()
+            .filter(|key| !self.options.iter().any(|(name, _)| name == key))
+            .collect::<Vec<_>>();
+
+        unknown_options.sort();
+
+        for key in unknown_options {
+            report.unsupported(
+                file,
+                format!("{pointer}{}", create_pointer([key])),
+                format!("Option is not supported by the {executor} executor"),
+            );
+        }
+
+        Ok(result)
+    }
+}
@@ -3,21 +3,23 @@
 
 #![allow(dead_code)]
 
+use crate::nx_project_json::NxProjectJson;
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
 use starbase_utils::json::JsonValue;
+use std::collections::BTreeMap;
 
 /// Only fields that are compatible with moon are documented,
 /// anything else is ignored!
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[serde(untagged)]
 pub enum StringOrList {
     List(Vec<String>),
     String(String),
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[serde(untagged)]
 pub enum NxDependsOn {
     Object {
@@ -29,7 +31,7 @@ pub enum NxDependsOn {
     String(String),
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[serde(untagged)]
 pub enum NxInput {
     Dep {
@@ -74,10 +76,27 @@ pub struct NxTargetOptions {
     pub configurations: Option<FxHashMap<String, FxHashMap<String, JsonValue>>>,
     pub depends_on: Option<Vec<NxDependsOn>>,
     pub default_configuration: Option<String>,
+    #[serde(alias = "builder")]
     pub executor: Option<String>,
     pub inputs: Option<Vec<NxInput>>,
     pub options: Option<FxHashMap<String, JsonValue>>,
     pub outputs: Option<Vec<String>>,
+    // Not supported:
+    // continuous, parallelism, syncGenerators, metadata, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum NxPlugin {
+    Object {
+        plugin: String,
+        options: Option<FxHashMap<String, JsonValue>>,
+        include: Option<Vec<String>>,
+        exclude: Option<Vec<String>>,
+    },
+    String(String),
 }
 
 #[derive(Default, Deserialize)]
@@ -93,15 +112,27 @@ pub struct NxJson {
     pub affected: Option<NxAffected>,
     pub default_base: Option<String>,
     pub named_inputs: Option<NxNamedInputs>,
+    pub plugins: Option<Vec<NxPlugin>>,
     pub target_defaults: Option<FxHashMap<String, NxTargetOptions>>,
     pub workspace_layout: Option<NxWorkspaceLayout>,
     // Not supported:
     // implicitDependencies, tasksRunnerOptions, release, generators,
-    // plugins, defaultProject, nxCloud*, parallel, cacheDirectory, useDaemonProcess
+    // defaultProject, nxCloud*, parallel, cacheDirectory, useDaemonProcess
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum NxWorkspaceProject {
+    Source(String),
+    Config(Box<NxProjectJson>),
 }
 
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NxWorkspaceJson {
-    pub projects: FxHashMap<String, String>,
+    pub projects: FxHashMap<String, NxWorkspaceProject>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
 }
@@ -1,23 +1,86 @@
+use crate::angular_builders::*;
+use crate::nx_executors::*;
 use crate::nx_json::*;
+use crate::nx_plugins::*;
 use crate::nx_project_json::*;
 use moon_common::Id;
 use moon_config::{
     FilePath, InputPath, OutputPath, PartialProjectDependsOn, PartialProjectMetadataConfig,
     PartialTaskArgs, PartialTaskConfig, PartialTaskDependency, PartialTaskOptionsConfig,
     PartialVcsConfig, PartialWorkspaceProjects, PlatformType, PortablePath, ProjectType,
-    TaskOptionEnvFile,
+    TaskMergeStrategy, TaskOptionEnvFile,
 };
 use moon_extension_common::migrator::*;
-use moon_pdk::{map_miette_error, AnyResult, MoonContext};
-use moon_target::Target;
-use rustc_hash::FxHashMap;
+use moon_extension_common::report::*;
+use moon_pdk::{anyhow, map_miette_error, AnyResult, MoonContext};
+use moon_target::{Target, TargetScope};
+use rustc_hash::{FxHashMap, FxHashSet};
 use starbase_utils::json::JsonValue;
-use std::collections::BTreeMap;
+use starbase_utils::{fs, glob};
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
+/// A task created from a target configuration, e.g. `build.production`.
+pub struct NxConfigurationTask {
+    pub project_source: String,
+    pub task_id: Id,
+    pub base_task_id: Id,
+    pub name: String,
+    pub file: String,
+    pub pointer: String,
+    /// Targets that the base target forwards its parameters to.
+    pub forwarded_targets: Vec<String>,
+}
+
+/// Implicit dependencies of a project, which may contain globs and negations,
+/// and can only be resolved once all project names are known.
+pub struct NxImplicitDependencies {
+    pub project_source: String,
+    pub file: String,
+    pub pointer: String,
+    pub dependencies: Vec<String>,
+}
+
+/// An input of a task that references dependency projects, like `^production`
+/// or `dependentTasksOutputFiles`, which can only be resolved once all projects
+/// and their dependencies are known. Inputs of target defaults have no project,
+/// and are resolved for every project.
+pub struct NxDependencyInput {
+    pub project_source: Option<String>,
+    pub task_id: Id,
+    pub file: String,
+    pub pointer: String,
+    pub input: NxInput,
+}
+
 pub struct NxMigrator {
     pub inner: Migrator,
+    /// Migrating an Angular CLI workspace (without Nx).
+    pub angular_cli: bool,
+    pub configuration_tasks: Vec<NxConfigurationTask>,
+    pub dependency_inputs: Vec<NxDependencyInput>,
+    /// Target defaults keyed by executor.
+    pub executor_defaults: FxHashMap<String, NxTargetOptions>,
+    pub implicit_dependencies: Vec<NxImplicitDependencies>,
+    /// Named inputs of `nx.json`.
+    pub named_inputs: NxNamedInputs,
+    /// Sources of projects, keyed by their package name.
+    pub package_names: BTreeMap<String, String>,
     pub package_manager: String,
+    /// Plugins that infer tasks from config files.
+    pub plugins: Vec<NxPluginEntry>,
+    /// Names of projects that a project depends on, through implicit
+    /// dependencies and `package.json`, keyed by its source.
+    pub project_dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// Names of projects, keyed by their source.
+    pub project_names: BTreeMap<String, String>,
+    /// Named inputs of projects, keyed by their source.
+    pub project_named_inputs: BTreeMap<String, NxNamedInputs>,
+    pub project_sources: BTreeSet<String>,
+    pub report: MigrationReport,
+    /// Targets that define their own inputs, which replace the inputs
+    /// of target defaults, keyed by the project source.
+    pub target_inputs: FxHashSet<(String, Id)>,
 }
 
 impl NxMigrator {
@@ -29,8 +92,22 @@ impl NxMigrator {
         }
 
         Ok(Self {
+            angular_cli: false,
+            configuration_tasks: vec![],
+            dependency_inputs: vec![],
+            executor_defaults: FxHashMap::default(),
+            implicit_dependencies: vec![],
+            named_inputs: NxNamedInputs::default(),
+            package_names: BTreeMap::default(),
             package_manager: migrator.detect_package_manager(),
+            plugins: vec![],
+            project_dependencies: BTreeMap::default(),
+            project_names: BTreeMap::default(),
+            project_named_inputs: BTreeMap::default(),
+            project_sources: BTreeSet::default(),
             inner: migrator,
+            report: MigrationReport::default(),
+            target_inputs: FxHashSet::default(),
         })
     }
 
@@ -69,6 +146,12 @@ impl NxMigrator {
     }
 
     pub fn migrate_root_config(&mut self, nx_json: NxJson) -> AnyResult<()> {
+        let file = "nx.json";
+
+        for (key, value) in &nx_json.unknown {
+            report_unknown_setting(&mut self.report, file, "", key, value);
+        }
+
         if let Some(default_branch) = nx_json
             .default_base
             .or_else(|| nx_json.affected.and_then(|aff| aff.default_base))
@@ -82,6 +165,8 @@ impl NxMigrator {
 
         if let Some(named_inputs) = nx_json.named_inputs {
             if !named_inputs.is_empty() {
+                self.named_inputs = named_inputs.clone();
+
                 let file_groups = self
                     .inner
                     .load_tasks_platform_config()?
@@ -89,7 +174,13 @@ impl NxMigrator {
                     .get_or_insert(FxHashMap::default());
 
                 for (name, raw_inputs) in named_inputs {
-                    let group = migrate_inputs(&raw_inputs, true)?;
+                    let group = migrate_inputs(
+                        &raw_inputs,
+                        true,
+                        &mut self.report,
+                        file,
+                        &create_pointer(["namedInputs", &name]),
+                    )?;
 
                     if !group.is_empty() {
                         file_groups.insert(create_id(name)?, group);
@@ -98,6 +189,41 @@ impl NxMigrator {
             }
         }
 
+        // https://nx.dev/concepts/inferred-tasks
+        for (index, plugin) in nx_json.plugins.unwrap_or_default().into_iter().enumerate() {
+            let pointer = create_pointer(["plugins", &index.to_string()]);
+
+            match find_plugin(plugin) {
+                Ok(entry) => {
+                    if entry
+                        .targets
+                        .iter()
+                        .any(|target| target.outputs_from_config)
+                    {
+                        self.report.approximated(
+                            file,
+                            pointer,
+                            format!(
+                                "Outputs of tasks inferred by {} are read from config files, configure them explicitly",
+                                entry.name
+                            ),
+                        );
+                    }
+
+                    self.plugins.push(entry);
+                }
+                Err(name) => {
+                    self.report.unsupported(
+                        file,
+                        pointer,
+                        format!(
+                            "Plugin {name} is not supported, define its inferred tasks explicitly"
+                        ),
+                    );
+                }
+            };
+        }
+
         if let Some(target_defaults) = nx_json.target_defaults {
             let tasks = self
                 .inner
@@ -105,11 +231,39 @@ impl NxMigrator {
                 .tasks
                 .get_or_insert(BTreeMap::default());
 
+            reserve_task_ids(
+                tasks,
+                target_defaults.keys().filter(|name| !name.contains(':')),
+            )?;
+
             for (name, target_config) in target_defaults {
-                tasks.insert(
-                    create_id(name)?,
-                    migrate_task(&target_config, &self.package_manager)?,
-                );
+                // Defaults keyed by an executor apply to all targets that use it,
+                // so they are merged into project tasks instead
+                if name.contains(':') {
+                    for (key, value) in &target_config.unknown {
+                        report_unknown_setting(
+                            &mut self.report,
+                            file,
+                            &create_pointer(["targetDefaults", &name]),
+                            key,
+                            value,
+                        );
+                    }
+
+                    self.executor_defaults.insert(name, target_config);
+
+                    continue;
+                }
+
+                self.dependency_inputs.extend(migrate_task(
+                    &target_config,
+                    &create_id(&name)?,
+                    tasks,
+                    &self.package_manager,
+                    &mut self.report,
+                    file,
+                    &create_pointer(["targetDefaults", &name]),
+                )?);
             }
         }
 
@@ -127,11 +281,59 @@ impl NxMigrator {
         Ok(())
     }
 
-    pub fn migrate_workspace_config(&mut self, workspace_json: NxWorkspaceJson) -> AnyResult<()> {
+    pub fn migrate_workspace_config(
+        &mut self,
+        file: &str,
+        workspace_json: NxWorkspaceJson,
+    ) -> AnyResult<()> {
         let mut projects = FxHashMap::default();
 
-        for (id, source) in workspace_json.projects {
-            projects.insert(create_id(id)?, source);
+        for (key, value) in &workspace_json.unknown {
+            // Only the format version, which is irrelevant to moon
+            if key == "version" {
+                continue;
+            }
+
+            report_unknown_setting(&mut self.report, file, "", key, value);
+        }
+
+        for (name, project) in workspace_json.projects {
+            let id = create_id(&name)?;
+
+            match project {
+                NxWorkspaceProject::Source(source) => {
+                    projects.insert(id, source);
+                }
+                // Inline project definitions, which have no project.json
+                NxWorkspaceProject::Config(mut project_json) => {
+                    let Some(project_source) = project_json.root.as_deref().map(normalize_root)
+                    else {
+                        return Err(anyhow!(
+                            "Project <id>{name}</id> in <file>{file}</file> must define a root."
+                        ));
+                    };
+
+                    projects.insert(
+                        id,
+                        if project_source.is_empty() {
+                            ".".into()
+                        } else {
+                            project_source.clone()
+                        },
+                    );
+
+                    if project_json.name.is_none() {
+                        project_json.name = Some(name.clone());
+                    }
+
+                    self.migrate_project(
+                        &project_source,
+                        *project_json,
+                        file,
+                        &create_pointer(["projects", &name]),
+                    )?;
+                }
+            };
         }
 
         if !projects.is_empty() {
@@ -147,28 +349,121 @@ impl NxMigrator {
         project_source: &str,
         project_json: NxProjectJson,
     ) -> AnyResult<()> {
+        self.migrate_project(
+            project_source,
+            project_json,
+            &to_source_file(project_source, "project.json"),
+            "",
+        )
+    }
+
+    pub fn migrate_project_package_config(
+        &mut self,
+        project_source: &str,
+        nx_package_json: PackageJsonWithNx,
+    ) -> AnyResult<()> {
+        // The root package is not a project, unless it has a project.json
+        if !project_source.is_empty() {
+            self.project_sources.insert(project_source.to_owned());
+        }
+
+        // A name in project.json takes precedence
+        if let Some(name) = nx_package_json.name {
+            self.package_names
+                .insert(name.clone(), project_source.to_owned());
+
+            self.project_names
+                .entry(project_source.to_owned())
+                .or_insert(name);
+        }
+
+        // Packages that aren't in the workspace are filtered out once all projects are known
+        let package_dependencies = nx_package_json
+            .dependencies
+            .iter()
+            .chain(nx_package_json.dev_dependencies.iter())
+            .chain(nx_package_json.optional_dependencies.iter())
+            .chain(nx_package_json.peer_dependencies.iter())
+            .flat_map(|deps| deps.keys().cloned())
+            .collect::<Vec<_>>();
+
+        if !package_dependencies.is_empty() {
+            self.project_dependencies
+                .entry(project_source.to_owned())
+                .or_default()
+                .extend(package_dependencies);
+        }
+
+        if let Some(nx) = nx_package_json.nx {
+            self.migrate_project(
+                project_source,
+                nx,
+                &to_source_file(project_source, "package.json"),
+                "/nx",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn migrate_project(
+        &mut self,
+        project_source: &str,
+        mut project_json: NxProjectJson,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<()> {
+        // The root takes precedence over the location of the config file
+        let root = project_json.root.as_deref().map(normalize_root);
+        let project_source = root.as_deref().unwrap_or(project_source);
+
+        if let Some(source_root) = project_json.source_root.as_deref() {
+            let token = create_source_root_token(project_source, &normalize_root(source_root));
+
+            replace_source_root(&mut project_json, &token);
+        }
+
+        self.project_sources.insert(project_source.to_owned());
+
+        let project_name = project_json.name.clone().unwrap_or_default();
         let config = self.inner.load_project_config(project_source)?;
 
+        for (key, value) in &project_json.unknown {
+            report_unknown_setting(&mut self.report, file, pointer, key, value);
+        }
+
         if let Some(name) = project_json.name {
-            config.id = Some(create_id(name)?);
+            config.id = Some(create_id(&name)?);
+
+            self.project_names.insert(project_source.to_owned(), name);
         }
 
         if let Some(implicit_dependencies) = project_json.implicit_dependencies {
             if !implicit_dependencies.is_empty() {
-                let depends_on = config.depends_on.get_or_insert(vec![]);
-
-                for dep in implicit_dependencies {
-                    depends_on.push(PartialProjectDependsOn::String(create_id(dep)?));
-                }
+                self.implicit_dependencies.push(NxImplicitDependencies {
+                    project_source: project_source.to_owned(),
+                    file: file.to_owned(),
+                    pointer: format!("{pointer}/implicitDependencies"),
+                    dependencies: implicit_dependencies,
+                });
             }
         }
 
         if let Some(named_inputs) = project_json.named_inputs {
             if !named_inputs.is_empty() {
+                self.project_named_inputs
+                    .insert(project_source.to_owned(), named_inputs.clone());
+
                 let file_groups = config.file_groups.get_or_insert(FxHashMap::default());
 
                 for (name, raw_inputs) in named_inputs {
-                    let group = migrate_inputs(&raw_inputs, true)?;
+                    let group = migrate_inputs(
+                        &raw_inputs,
+                        true,
+                        &mut self.report,
+                        file,
+                        &format!("{pointer}{}", create_pointer(["namedInputs", &name])),
+                    )?;
 
                     if !group.is_empty() {
                         file_groups.insert(create_id(name)?, group);
@@ -196,27 +491,211 @@ impl NxMigrator {
         if let Some(targets) = project_json.targets {
             let tasks = config.tasks.get_or_insert(BTreeMap::default());
 
-            for (name, target) in targets {
-                let task_id = create_id(name)?;
+            reserve_task_ids(tasks, targets.keys())?;
 
-                tasks.insert(
-                    task_id.clone(),
-                    migrate_task(&target, &self.package_manager)?,
-                );
+            for (name, mut target) in targets {
+                if let Some(defaults) = target
+                    .executor
+                    .as_ref()
+                    .and_then(|executor| self.executor_defaults.get(executor))
+                {
+                    target = merge_target_defaults(target, defaults);
+                }
+
+                let task_id = create_id(&name)?;
+                let target_pointer = format!("{pointer}{}", create_pointer(["targets", &name]));
+
+                // Running the target without a configuration applies the default
+                // configuration, so merge its options into the base task.
+                // The Angular CLI applies the default configuration itself.
+                let mut default_target = None;
+
+                if let Some(default_name) = target
+                    .default_configuration
+                    .as_ref()
+                    .filter(|_| !self.angular_cli)
+                {
+                    match target
+                        .configurations
+                        .as_ref()
+                        .and_then(|configs| configs.get(default_name))
+                    {
+                        Some(default_options) => {
+                            default_target =
+                                Some(merge_configuration_options(&target, default_options));
+                        }
+                        None => {
+                            self.report.unsupported(
+                                file,
+                                format!("{target_pointer}/defaultConfiguration"),
+                                format!("Configuration {default_name} does not exist"),
+                            );
+                        }
+                    };
+                }
+
+                if self.angular_cli {
+                    tasks.insert(
+                        task_id.clone(),
+                        migrate_angular_task(&project_name, &name, &target)?,
+                    );
+                } else {
+                    let dependency_inputs = migrate_task(
+                        default_target.as_ref().unwrap_or(&target),
+                        &task_id,
+                        tasks,
+                        &self.package_manager,
+                        &mut self.report,
+                        file,
+                        &target_pointer,
+                    )?;
+
+                    self.dependency_inputs
+                        .extend(
+                            dependency_inputs
+                                .into_iter()
+                                .map(|input| NxDependencyInput {
+                                    project_source: Some(project_source.to_owned()),
+                                    ..input
+                                }),
+                        );
+                }
+
+                if target.inputs.is_some() {
+                    self.target_inputs
+                        .insert((project_source.to_owned(), task_id.clone()));
+                }
 
                 // https://nx.dev/concepts/executors-and-configurations#use-task-configurations
-                if let Some(configurations) = target.configurations {
+                if let Some(configurations) = &target.configurations {
+                    let forwarded_targets = target
+                        .depends_on
+                        .iter()
+                        .flatten()
+                        .filter_map(|dep| match dep {
+                            NxDependsOn::Object { target, params, .. }
+                                if params.as_deref() == Some("forward") =>
+                            {
+                                Some(target.to_owned())
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+
+                    let native = target
+                        .executor
+                        .as_deref()
+                        .filter(|_| !self.angular_cli)
+                        .and_then(|executor| {
+                            find_executor(executor).map(|native| (executor, native))
+                        });
+
                     for (config_name, config_options) in configurations {
-                        tasks.insert(
-                            create_id(format!("{task_id}.{config_name}"))?,
-                            PartialTaskConfig {
-                                extends: Some(task_id.clone()),
-                                args: Some(PartialTaskArgs::List(migrate_options_to_args(
-                                    &config_options,
-                                ))),
-                                ..PartialTaskConfig::default()
-                            },
+                        let config_name = config_name.to_owned();
+                        let config_task_id = create_id(format!("{task_id}.{config_name}"))?;
+                        let config_pointer = format!(
+                            "{target_pointer}{}",
+                            create_pointer(["configurations", &config_name])
                         );
+                        let args = match &native {
+                            _ if self.angular_cli => {
+                                vec!["--configuration".into(), config_name.clone()]
+                            }
+                            Some((executor, native)) => {
+                                native
+                                    .migrate_options(
+                                        config_options,
+                                        true,
+                                        &mut self.report,
+                                        executor,
+                                        file,
+                                        &config_pointer,
+                                    )?
+                                    .args
+                            }
+                            None => migrate_options_to_args(config_options),
+                        };
+
+                        if default_target.is_none() {
+                            tasks.insert(
+                                config_task_id.clone(),
+                                PartialTaskConfig {
+                                    extends: Some(task_id.clone()),
+                                    args: if args.is_empty() && native.is_some() {
+                                        None
+                                    } else {
+                                        Some(PartialTaskArgs::List(args))
+                                    },
+                                    ..PartialTaskConfig::default()
+                                },
+                            );
+                        } else if target.default_configuration.as_ref() == Some(&config_name) {
+                            // Identical to the base task
+                            tasks.insert(
+                                config_task_id.clone(),
+                                PartialTaskConfig {
+                                    extends: Some(task_id.clone()),
+                                    ..PartialTaskConfig::default()
+                                },
+                            );
+                        } else {
+                            // Options of the default configuration must not leak into
+                            // other configurations, so create the task from scratch
+                            let mut config_report = MigrationReport::default();
+
+                            let dependency_inputs = migrate_task(
+                                &merge_configuration_options(&target, config_options),
+                                &config_task_id,
+                                tasks,
+                                &self.package_manager,
+                                &mut config_report,
+                                file,
+                                &target_pointer,
+                            )?;
+
+                            self.dependency_inputs
+                                .extend(dependency_inputs.into_iter().map(|input| {
+                                    NxDependencyInput {
+                                        project_source: Some(project_source.to_owned()),
+                                        ..input
+                                    }
+                                }));
+
+                            // Point to options where the configuration defined them,
+                            // while settings of the target that were already reported
+                            // are deduplicated by the report
+                            let options_pointer = format!("{target_pointer}/options");
+
+                            for entry in config_report.entries {
+                                let pointer = match entry.pointer.strip_prefix(&options_pointer) {
+                                    Some(option_pointer)
+                                        if config_options.keys().any(|key| {
+                                            let key_pointer = create_pointer([key]);
+
+                                            option_pointer == key_pointer
+                                                || option_pointer
+                                                    .starts_with(&format!("{key_pointer}/"))
+                                        }) =>
+                                    {
+                                        format!("{config_pointer}{option_pointer}")
+                                    }
+                                    _ => entry.pointer,
+                                };
+
+                                self.report
+                                    .add(entry.kind, entry.file, pointer, entry.reason);
+                            }
+                        }
+
+                        self.configuration_tasks.push(NxConfigurationTask {
+                            project_source: project_source.to_owned(),
+                            task_id: config_task_id,
+                            base_task_id: task_id.clone(),
+                            name: config_name,
+                            file: file.to_owned(),
+                            pointer: config_pointer,
+                            forwarded_targets: forwarded_targets.clone(),
+                        });
                     }
                 }
             }
@@ -233,17 +712,636 @@ impl NxMigrator {
 
         Ok(())
     }
+}
 
-    pub fn migrate_project_package_config(
+impl NxMigrator {
+    /// Create the tasks that plugins would have inferred from config files
+    /// in the project root. Targets defined in the project take precedence.
+    pub fn migrate_inferred_tasks(&mut self, project_source: &str) -> AnyResult<()> {
+        let project_root = self.inner.root.join(project_source);
+        let mut tasks = BTreeMap::default();
+
+        for plugin in &self.plugins {
+            let include = if plugin.include.is_empty() {
+                vec!["**/*".to_owned()]
+            } else {
+                plugin.include.clone()
+            };
+            let globset = glob::GlobSet::new_split(&include, &plugin.exclude)?;
+
+            for target in plugin.targets {
+                let has_config = target.config_files.iter().any(|file_name| {
+                    let config_path = project_root.join(file_name);
+
+                    config_path.exists()
+                        && globset.matches(to_source_file(project_source, file_name))
+                        && target.config_contains.map_or(true, |text| {
+                            fs::read_file(&config_path).is_ok_and(|content| content.contains(text))
+                        })
+                });
+
+                if !has_config {
+                    continue;
+                }
+
+                let mut config = PartialTaskConfig {
+                    command: Some(PartialTaskArgs::String(target.command.to_owned())),
+                    ..PartialTaskConfig::default()
+                };
+
+                if !target.outputs.is_empty() {
+                    let mut outputs = vec![];
+
+                    for output in target.outputs {
+                        outputs.push(OutputPath::from_str(output)?);
+                    }
+
+                    config.outputs = Some(outputs);
+                }
+
+                if target.local {
+                    config.local = Some(true);
+                }
+
+                tasks
+                    .entry(create_id(plugin.get_target_name(target))?)
+                    .or_insert(config);
+            }
+        }
+
+        if !tasks.is_empty() {
+            let project_tasks = self
+                .inner
+                .load_project_config(project_source)?
+                .tasks
+                .get_or_insert(BTreeMap::default());
+
+            for (task_id, task) in tasks {
+                project_tasks.entry(task_id).or_insert(task);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve implicit dependencies against the names of all projects.
+    /// Globs expand to every matching project, while negations remove
+    /// previously matched projects. Projects that moon infers from
+    /// `package.json` can't be excluded, so those negations are reported.
+    pub fn migrate_implicit_dependencies(&mut self) -> AnyResult<()> {
+        let mut project_names = BTreeSet::default();
+
+        for project_source in &self.project_sources {
+            if let Some(name) = self.project_names.get(project_source) {
+                project_names.insert(name.as_str());
+            } else if let Some(dir_name) = project_source.rsplit('/').next() {
+                if !dir_name.is_empty() {
+                    project_names.insert(dir_name);
+                }
+            }
+        }
+
+        for implicit in &self.implicit_dependencies {
+            let mut dependencies: Vec<&str> = vec![];
+
+            for (index, dep) in implicit.dependencies.iter().enumerate() {
+                let (negated, pattern) = match dep.strip_prefix('!') {
+                    Some(pattern) => (true, pattern),
+                    None => (false, dep.as_str()),
+                };
+
+                let matches = if glob::is_glob(pattern) {
+                    let globset = glob::GlobSet::new([pattern])?;
+
+                    project_names
+                        .iter()
+                        .filter(|name| globset.matches(name))
+                        .copied()
+                        .collect::<Vec<_>>()
+                } else {
+                    project_names
+                        .get(pattern)
+                        .copied()
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                };
+
+                if matches.is_empty() {
+                    return Err(anyhow!(
+                        "Implicit dependency <id>{dep}</id> in <file>{}</file> does not match any project.",
+                        implicit.file
+                    ));
+                }
+
+                if negated {
+                    let count = dependencies.len();
+
+                    dependencies.retain(|name| !matches.contains(name));
+
+                    if dependencies.len() == count {
+                        self.report.approximated(
+                            &implicit.file,
+                            format!("{}/{index}", implicit.pointer),
+                            format!(
+                                "Negated dependency {pattern} can't exclude dependencies inferred from package.json"
+                            ),
+                        );
+                    }
+                } else {
+                    for name in matches {
+                        if !dependencies.contains(&name) {
+                            dependencies.push(name);
+                        }
+                    }
+                }
+            }
+
+            if dependencies.is_empty() {
+                continue;
+            }
+
+            self.project_dependencies
+                .entry(implicit.project_source.clone())
+                .or_default()
+                .extend(dependencies.iter().map(|name| name.to_string()));
+
+            let depends_on = self
+                .inner
+                .load_project_config(&implicit.project_source)?
+                .depends_on
+                .get_or_insert(vec![]);
+
+            for name in dependencies {
+                depends_on.push(PartialProjectDependsOn::String(create_id(name)?));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Running a target with a configuration also runs its dependencies with
+    /// the same configuration, so once all projects have been migrated,
+    /// point configuration tasks at the matching configuration tasks.
+    /// The options of a configuration are not parameters, so targets that
+    /// forward their parameters to a dependency without a matching
+    /// configuration keep the plain dependency.
+    pub fn migrate_configuration_deps(&mut self) -> AnyResult<()> {
+        let all_task_ids = self
+            .configuration_tasks
+            .iter()
+            .map(|task| (task.project_source.as_str(), task.task_id.clone()))
+            .collect::<FxHashSet<_>>();
+
+        for config_task in &self.configuration_tasks {
+            let tasks = self
+                .inner
+                .load_project_config(&config_task.project_source)?
+                .tasks
+                .get_or_insert(BTreeMap::default());
+
+            let Some(base_deps) = tasks
+                .get(&config_task.base_task_id)
+                .and_then(|task| task.deps.clone())
+            else {
+                continue;
+            };
+
+            let mut deps = vec![];
+
+            for dep in &base_deps {
+                let PartialTaskDependency::Target(target) = dep else {
+                    deps.push(dep.clone());
+                    continue;
+                };
+
+                let task_id = create_id(format!("{}.{}", target.task_id, config_task.name))?;
+                let exists = match &target.scope {
+                    TargetScope::OwnSelf => tasks.contains_key(&task_id),
+                    _ => all_task_ids.iter().any(|(source, id)| {
+                        *source != config_task.project_source && *id == task_id
+                    }),
+                };
+
+                if exists {
+                    deps.push(PartialTaskDependency::Target(
+                        Target::parse(format!("{}:{task_id}", target.scope).as_str())
+                            .map_err(map_miette_error)?,
+                    ));
+                } else {
+                    if config_task
+                        .forwarded_targets
+                        .iter()
+                        .any(|name| target.task_id == name.as_str())
+                    {
+                        self.report.approximated(
+                            &config_task.file,
+                            &config_task.pointer,
+                            format!(
+                                "Dependency {target} has no {} configuration, so it runs without one instead of with the forwarded parameters",
+                                config_task.name
+                            ),
+                        );
+                    }
+
+                    deps.push(dep.clone());
+                }
+            }
+
+            if deps != base_deps {
+                if let Some(task) = tasks.get_mut(&config_task.task_id) {
+                    task.deps = Some(deps);
+                    task.options
+                        .get_or_insert(PartialTaskOptionsConfig::default())
+                        .merge_deps = Some(TaskMergeStrategy::Replace);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Once all projects and their dependencies are known, resolve inputs that
+    /// reference dependency projects into workspace-relative inputs: named inputs
+    /// of dependency projects (`^production`) into their files, and outputs of
+    /// dependency tasks (`dependentTasksOutputFiles`) into the matching outputs.
+    /// Dependencies can only be known from implicit dependencies and `package.json`,
+    /// so inputs that can't be resolved for any project are reported.
+    pub fn migrate_dependency_inputs(&mut self) -> AnyResult<()> {
+        let mut project_sources = BTreeMap::default();
+
+        for project_source in &self.project_sources {
+            let name = match self.project_names.get(project_source) {
+                Some(name) => name.as_str(),
+                None => project_source.rsplit('/').next().unwrap_or_default(),
+            };
+
+            project_sources.insert(name.to_owned(), project_source.to_owned());
+        }
+
+        for (name, project_source) in &self.package_names {
+            project_sources
+                .entry(name.to_owned())
+                .or_insert_with(|| project_source.to_owned());
+        }
+
+        let mut direct_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::default();
+
+        for (project_source, names) in &self.project_dependencies {
+            direct_deps.insert(
+                project_source.to_owned(),
+                names
+                    .iter()
+                    .filter_map(|name| project_sources.get(name))
+                    .filter(|dep_source| *dep_source != project_source)
+                    .cloned()
+                    .collect(),
+            );
+        }
+
+        // Inputs of target defaults are resolved first, so that
+        // inputs of projects are appended after them
+        let mut dependency_inputs = std::mem::take(&mut self.dependency_inputs);
+
+        dependency_inputs.sort_by_key(|input| input.project_source.is_some());
+
+        for dependency_input in &dependency_inputs {
+            let project_sources = match &dependency_input.project_source {
+                Some(project_source) => vec![project_source.to_owned()],
+                None => self
+                    .project_sources
+                    .iter()
+                    .filter(|project_source| {
+                        !self.target_inputs.contains(&(
+                            project_source.to_string(),
+                            dependency_input.task_id.clone(),
+                        ))
+                    })
+                    .cloned()
+                    .collect(),
+            };
+
+            let mut resolved = false;
+
+            for project_source in project_sources {
+                let Some(task) = self.get_project_task(&project_source, &dependency_input.task_id)
+                else {
+                    continue;
+                };
+
+                let inputs = match &dependency_input.input {
+                    NxInput::DepOutput {
+                        dependent_tasks_output_files,
+                        transitive,
+                    } => {
+                        // Dependencies of inherited tasks are merged with those of the project
+                        let mut task_deps = task.deps.clone().unwrap_or_default();
+
+                        if let Some(deps) = self
+                            .inner
+                            .load_tasks_platform_config()?
+                            .tasks
+                            .as_ref()
+                            .and_then(|tasks| tasks.get(&dependency_input.task_id))
+                            .and_then(|task| task.deps.as_ref())
+                        {
+                            task_deps.extend(deps.iter().cloned());
+                        }
+
+                        self.resolve_dependency_outputs(
+                            &project_source,
+                            &task_deps,
+                            dependent_tasks_output_files,
+                            transitive.unwrap_or_default(),
+                            &direct_deps,
+                        )?
+                    }
+                    input => {
+                        let Some(name) = get_dependency_named_input(input) else {
+                            continue;
+                        };
+
+                        let mut inputs = vec![];
+
+                        for dep_source in
+                            collect_dependency_sources(&project_source, true, &direct_deps)
+                        {
+                            for input in self.resolve_named_input(
+                                &dep_source,
+                                name,
+                                &mut BTreeSet::default(),
+                            )? {
+                                if !inputs.contains(&input) {
+                                    inputs.push(input);
+                                }
+                            }
+                        }
+
+                        inputs
+                    }
+                };
+
+                if inputs.is_empty() {
+                    continue;
+                }
+
+                resolved = true;
+
+                let task_inputs = self
+                    .inner
+                    .load_project_config(&project_source)?
+                    .tasks
+                    .get_or_insert(BTreeMap::default())
+                    .entry(dependency_input.task_id.clone())
+                    .or_default()
+                    .inputs
+                    .get_or_insert(vec![]);
+
+                for input in inputs {
+                    if !task_inputs.contains(&input) {
+                        task_inputs.push(input);
+                    }
+                }
+            }
+
+            if !resolved {
+                self.report.approximated(
+                    &dependency_input.file,
+                    &dependency_input.pointer,
+                    if matches!(dependency_input.input, NxInput::DepOutput { .. }) {
+                        "Outputs of dependency tasks are not hashed, as none of the tasks it depends on have known outputs"
+                    } else {
+                        "Inputs of dependency projects are not hashed, as no dependency projects are known from implicit dependencies or package.json"
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find a migrated task of a project, without loading the config
+    /// of projects that have no migrated settings.
+    fn get_project_task(&self, project_source: &str, task_id: &Id) -> Option<&PartialTaskConfig> {
+        self.inner
+            .project_configs
+            .get(&self.inner.root.join(project_source).join("moon.yml"))
+            .and_then(|config| config.tasks.as_ref())
+            .and_then(|tasks| tasks.get(task_id))
+    }
+
+    /// Resolve a named input of a dependency project into workspace-relative inputs.
+    /// Named inputs of the project take precedence over those of `nx.json`.
+    fn resolve_named_input(
+        &self,
+        dep_source: &str,
+        name: &str,
+        visited: &mut BTreeSet<String>,
+    ) -> AnyResult<Vec<InputPath>> {
+        let mut inputs = vec![];
+
+        if !visited.insert(name.to_owned()) {
+            return Ok(inputs);
+        }
+
+        let raw_inputs = match self
+            .project_named_inputs
+            .get(dep_source)
+            .and_then(|named_inputs| named_inputs.get(name))
+            .or_else(|| self.named_inputs.get(name))
+        {
+            Some(raw_inputs) => raw_inputs.to_owned(),
+            // https://nx.dev/reference/inputs#default-inputs
+            None if name == "default" => vec![NxInput::Source("{projectRoot}/**/*".into())],
+            None => return Ok(inputs),
+        };
+
+        for input in raw_inputs {
+            match input {
+                NxInput::Env { env } => {
+                    inputs.push(InputPath::EnvVar(env));
+                }
+                NxInput::Fileset { fileset } => {
+                    inputs.push(to_dependency_input(
+                        &replace_tokens(&fileset, true),
+                        dep_source,
+                    )?);
+                }
+                NxInput::Source(source) if is_path_or_glob(&source) => {
+                    inputs.push(to_dependency_input(
+                        &replace_tokens(&source, true),
+                        dep_source,
+                    )?);
+                }
+                // Dependencies of the dependency project are already included
+                NxInput::Source(source) if !source.starts_with('^') => {
+                    inputs.extend(self.resolve_named_input(dep_source, &source, visited)?);
+                }
+                NxInput::Dep {
+                    dependencies,
+                    projects: None,
+                    input,
+                } if dependencies != Some(true) => {
+                    inputs.extend(self.resolve_named_input(dep_source, &input, visited)?);
+                }
+                _ => {}
+            };
+        }
+
+        Ok(inputs)
+    }
+
+    /// Resolve the outputs of the tasks that a task depends on, which match the pattern.
+    fn resolve_dependency_outputs(
         &mut self,
         project_source: &str,
-        nx_package_json: PackageJsonWithNx,
-    ) -> AnyResult<()> {
-        if let Some(nx) = nx_package_json.nx {
-            self.migrate_project_config(project_source, nx)?;
+        task_deps: &[PartialTaskDependency],
+        pattern: &str,
+        transitive: bool,
+        direct_deps: &BTreeMap<String, BTreeSet<String>>,
+    ) -> AnyResult<Vec<InputPath>> {
+        let pattern = replace_tokens(pattern, true);
+        let mut inputs = vec![];
+
+        for dep in task_deps {
+            let target = match dep {
+                PartialTaskDependency::Target(target) => target,
+                PartialTaskDependency::Config(config) => match &config.target {
+                    Some(target) => target,
+                    None => continue,
+                },
+            };
+
+            let dep_sources = match &target.scope {
+                TargetScope::Deps => {
+                    collect_dependency_sources(project_source, transitive, direct_deps)
+                }
+                TargetScope::OwnSelf => vec![project_source.to_owned()],
+                _ => continue,
+            };
+
+            for dep_source in dep_sources {
+                let outputs = match self
+                    .get_project_task(&dep_source, &target.task_id)
+                    .and_then(|task| task.outputs.clone())
+                {
+                    Some(outputs) => outputs,
+                    None => match self
+                        .inner
+                        .load_tasks_platform_config()?
+                        .tasks
+                        .as_ref()
+                        .and_then(|tasks| tasks.get(&target.task_id))
+                        .and_then(|task| task.outputs.clone())
+                    {
+                        Some(outputs) => outputs,
+                        None => continue,
+                    },
+                };
+
+                for output in outputs {
+                    let path = match &output {
+                        OutputPath::ProjectFile(path) | OutputPath::ProjectGlob(path) => {
+                            path.to_owned()
+                        }
+                        OutputPath::WorkspaceFile(path) | OutputPath::WorkspaceGlob(path) => {
+                            format!("/{path}")
+                        }
+                        _ => continue,
+                    };
+
+                    // Outputs that are globs or directories are narrowed down to the pattern
+                    let path = if glob::is_glob(&path) || !path.contains('.') {
+                        let dir = path
+                            .split('/')
+                            .take_while(|part| !glob::is_glob(part))
+                            .collect::<Vec<_>>()
+                            .join("/");
+
+                        if dir.is_empty() {
+                            pattern.to_owned()
+                        } else {
+                            format!("{dir}/{}", pattern.trim_start_matches("./"))
+                        }
+                    } else if glob::GlobSet::new([pattern.as_str()])?.matches(&path)
+                        || glob::GlobSet::new([format!("**/{pattern}").as_str()])?.matches(&path)
+                    {
+                        path
+                    } else {
+                        continue;
+                    };
+
+                    let input = to_dependency_input(&path, &dep_source)?;
+
+                    if !inputs.contains(&input) {
+                        inputs.push(input);
+                    }
+                }
+            }
         }
 
-        Ok(())
+        Ok(inputs)
+    }
+}
+
+/// Collect the sources of the dependency projects of a project,
+/// optionally including the dependencies of those projects.
+fn collect_dependency_sources(
+    project_source: &str,
+    transitive: bool,
+    direct_deps: &BTreeMap<String, BTreeSet<String>>,
+) -> Vec<String> {
+    let mut sources = vec![];
+    let mut queue = vec![project_source.to_owned()];
+
+    while let Some(source) = queue.pop() {
+        for dep_source in direct_deps.get(&source).into_iter().flatten() {
+            if dep_source != project_source && !sources.contains(dep_source) {
+                sources.push(dep_source.to_owned());
+
+                if transitive {
+                    queue.push(dep_source.to_owned());
+                }
+            }
+        }
+    }
+
+    sources
+}
+
+fn to_source_file(project_source: &str, file_name: &str) -> String {
+    if project_source.is_empty() {
+        file_name.to_owned()
+    } else {
+        format!("{project_source}/{file_name}")
+    }
+}
+
+fn report_unknown_setting(
+    report: &mut MigrationReport,
+    file: &str,
+    pointer: &str,
+    key: &str,
+    value: &JsonValue,
+) {
+    let reason = match key {
+        "$schema" => return,
+        "generators" | "schematics" | "prefix" | "newProjectRoot" => {
+            "Generators are not supported, use moon templates instead"
+        }
+        "cli" => "CLI settings are not supported",
+        "implicitDependencies" => {
+            "Workspace-wide implicit dependencies are not supported, use implicit inputs instead"
+        }
+        "nxCloudAccessToken" | "nxCloudEncryptionKey" | "nxCloudId" | "nxCloudUrl" => {
+            "Nx Cloud is not supported, use moon's remote caching instead"
+        }
+        "release" => "Release management is not supported",
+        "tasksRunnerOptions" => "Task runner options are not supported",
+        _ => "Setting is not supported",
+    };
+
+    if !matches!(value, JsonValue::Null) {
+        report.unsupported(file, format!("{pointer}{}", create_pointer([key])), reason);
     }
 }
 
@@ -257,7 +1355,7 @@ fn is_path_or_glob(value: &str) -> bool {
         && !value.contains(' ')
 }
 
-fn replace_tokens(value: &str, for_sources: bool) -> String {
+pub fn replace_tokens(value: &str, for_sources: bool) -> String {
     let mut result = value.replace("{projectName}", "$project");
 
     if for_sources {
@@ -287,19 +1385,197 @@ fn replace_tokens(value: &str, for_sources: bool) -> String {
     result
 }
 
-fn migrate_inputs(raw_inputs: &[NxInput], for_file_groups: bool) -> AnyResult<Vec<InputPath>> {
+fn normalize_root(root: &str) -> String {
+    let root = root.trim_start_matches("./").trim_end_matches('/');
+
+    if root == "." {
+        String::new()
+    } else {
+        root.to_owned()
+    }
+}
+
+/// Convert the source root to a token that is relative to the project root
+/// when possible, so that it's handled by `replace_tokens`.
+fn create_source_root_token(project_source: &str, source_root: &str) -> String {
+    if source_root == project_source {
+        return "{projectRoot}".into();
+    }
+
+    let rel_source_root = if project_source.is_empty() {
+        Some(source_root)
+    } else {
+        source_root
+            .strip_prefix(project_source)
+            .and_then(|path| path.strip_prefix('/'))
+    };
+
+    match rel_source_root {
+        Some(path) => format!("{{projectRoot}}/{path}"),
+        None => format!("{{workspaceRoot}}/{source_root}"),
+    }
+}
+
+fn replace_source_root(project_json: &mut NxProjectJson, token: &str) {
+    fn replace_string(value: &mut String, token: &str) {
+        if value.contains("{sourceRoot}") {
+            *value = value.replace("{sourceRoot}", token);
+        }
+    }
+
+    fn replace_value(value: &mut JsonValue, token: &str) {
+        match value {
+            JsonValue::String(inner) => replace_string(inner, token),
+            JsonValue::Array(list) => list.iter_mut().for_each(|item| replace_value(item, token)),
+            JsonValue::Object(map) => map.values_mut().for_each(|item| replace_value(item, token)),
+            _ => {}
+        };
+    }
+
+    fn replace_inputs(inputs: &mut [NxInput], token: &str) {
+        for input in inputs {
+            match input {
+                NxInput::Fileset { fileset } => replace_string(fileset, token),
+                NxInput::Source(source) => replace_string(source, token),
+                _ => {}
+            };
+        }
+    }
+
+    for inputs in project_json
+        .named_inputs
+        .iter_mut()
+        .flat_map(|map| map.values_mut())
+    {
+        replace_inputs(inputs, token);
+    }
+
+    for target in project_json
+        .targets
+        .iter_mut()
+        .flat_map(|map| map.values_mut())
+    {
+        // Commands are not converted, so use the variable directly
+        if let Some(command) = &mut target.command {
+            replace_string(command, &replace_tokens(token, false));
+        }
+
+        if let Some(inputs) = &mut target.inputs {
+            replace_inputs(inputs, token);
+        }
+
+        for output in target.outputs.iter_mut().flatten() {
+            replace_string(output, token);
+        }
+
+        for value in target.options.iter_mut().flat_map(|map| map.values_mut()) {
+            replace_value(value, token);
+        }
+
+        for value in target
+            .configurations
+            .iter_mut()
+            .flat_map(|map| map.values_mut())
+            .flat_map(|map| map.values_mut())
+        {
+            replace_value(value, token);
+        }
+    }
+}
+
+/// The named input of dependency projects that the input hashes, like `production`
+/// for `^production`.
+fn get_dependency_named_input(input: &NxInput) -> Option<&str> {
+    match input {
+        NxInput::Dep {
+            dependencies,
+            projects,
+            input,
+        } => (projects.is_none() && *dependencies == Some(true)).then_some(input.as_str()),
+        NxInput::Source(source) => {
+            if is_path_or_glob(source) {
+                None
+            } else {
+                source.strip_prefix('^')
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the input references dependency projects or their tasks,
+/// which can only be resolved once all projects are known.
+fn is_dependency_input(input: &NxInput) -> bool {
+    matches!(input, NxInput::DepOutput { .. }) || get_dependency_named_input(input).is_some()
+}
+
+/// Convert a path or glob relative to a dependency project into
+/// a workspace-relative input.
+fn to_dependency_input(value: &str, dep_source: &str) -> AnyResult<InputPath> {
+    let (negated, path) = match value.strip_prefix('!') {
+        Some(path) => ("!", path),
+        None => ("", value),
+    };
+
+    let path = if path.starts_with('/') || dep_source.is_empty() {
+        path.trim_start_matches('/').to_owned()
+    } else {
+        format!("{dep_source}/{}", path.trim_start_matches("./"))
+    };
+
+    Ok(InputPath::from_str(&format!("{negated}/{path}"))?)
+}
+
+/// Inputs that reference dependency projects are skipped for tasks, as they're
+/// resolved once all projects are known, but can't be resolved for file groups.
+fn migrate_inputs(
+    raw_inputs: &[NxInput],
+    for_file_groups: bool,
+    report: &mut MigrationReport,
+    file: &str,
+    pointer: &str,
+) -> AnyResult<Vec<InputPath>> {
     let mut inputs = vec![];
 
-    for input in raw_inputs {
-        match input {
-            NxInput::Dep { .. } => {
-                // Not supported
+    for (index, input) in raw_inputs.iter().enumerate() {
+        let input_pointer = format!("{pointer}/{index}");
+
+        if is_dependency_input(input) {
+            if for_file_groups {
+                report.unsupported(
+                    file,
+                    input_pointer,
+                    "Inputs of dependency projects can't be referenced by file groups",
+                );
             }
-            NxInput::DepOutput { .. } => {
-                // Not supported
+
+            continue;
+        }
+
+        match input {
+            NxInput::Dep { projects, .. } => {
+                if projects.is_some() {
+                    report.unsupported(
+                        file,
+                        input_pointer,
+                        "Inputs from specific projects are not supported",
+                    );
+                } else {
+                    report.unsupported(
+                        file,
+                        input_pointer,
+                        "Inputs from dependency projects are not supported",
+                    );
+                }
             }
+            NxInput::DepOutput { .. } => {}
             NxInput::External { .. } => {
                 // Not supported, moon parses lockfiles automatically
+                report.approximated(
+                    file,
+                    input_pointer,
+                    "External dependencies are hashed automatically from the lockfile",
+                );
             }
             NxInput::Env { env } => {
                 inputs.push(InputPath::EnvVar(env.to_owned()));
@@ -309,6 +1585,11 @@ fn migrate_inputs(raw_inputs: &[NxInput], for_file_groups: bool) -> AnyResult<Ve
             }
             NxInput::Runtime { .. } => {
                 // Not supported, moon includes tool version automatically
+                report.approximated(
+                    file,
+                    input_pointer,
+                    "Runtime commands are not supported, tool versions are hashed automatically",
+                );
             }
             NxInput::Source(source) => {
                 // File path or glob
@@ -316,8 +1597,14 @@ fn migrate_inputs(raw_inputs: &[NxInput], for_file_groups: bool) -> AnyResult<Ve
                     inputs.push(InputPath::from_str(&replace_tokens(source, true))?);
                 }
                 // Named input
-                else if !source.starts_with('^') && !for_file_groups {
+                else if !for_file_groups {
                     inputs.push(InputPath::TokenFunc(format!("@group({source})")));
+                } else {
+                    report.unsupported(
+                        file,
+                        input_pointer,
+                        "Referencing other named inputs is not supported",
+                    );
                 }
             }
         };
@@ -326,7 +1613,7 @@ fn migrate_inputs(raw_inputs: &[NxInput], for_file_groups: bool) -> AnyResult<Ve
     Ok(inputs)
 }
 
-fn convert_value_to_string_without_quotes(value: &JsonValue) -> String {
+pub fn convert_value_to_string_without_quotes(value: &JsonValue) -> String {
     value
         .to_string()
         .trim_start_matches('"')
@@ -334,12 +1621,77 @@ fn convert_value_to_string_without_quotes(value: &JsonValue) -> String {
         .to_owned()
 }
 
+/// Apply target defaults to a target, with the target's own settings
+/// taking precedence, and options merged.
+fn merge_target_defaults(
+    nx_target: NxTargetOptions,
+    defaults: &NxTargetOptions,
+) -> NxTargetOptions {
+    let mut options = defaults.options.clone().unwrap_or_default();
+    options.extend(nx_target.options.unwrap_or_default());
+
+    let mut configurations = defaults.configurations.clone().unwrap_or_default();
+
+    for (name, config_options) in nx_target.configurations.unwrap_or_default() {
+        configurations
+            .entry(name)
+            .or_default()
+            .extend(config_options);
+    }
+
+    NxTargetOptions {
+        cache: nx_target.cache.or(defaults.cache),
+        command: nx_target.command,
+        configurations: if configurations.is_empty() {
+            None
+        } else {
+            Some(configurations)
+        },
+        depends_on: nx_target.depends_on.or_else(|| defaults.depends_on.clone()),
+        default_configuration: nx_target
+            .default_configuration
+            .or_else(|| defaults.default_configuration.clone()),
+        executor: nx_target.executor,
+        inputs: nx_target.inputs.or_else(|| defaults.inputs.clone()),
+        options: if options.is_empty() {
+            None
+        } else {
+            Some(options)
+        },
+        outputs: nx_target.outputs.or_else(|| defaults.outputs.clone()),
+        unknown: nx_target.unknown,
+    }
+}
+
+/// Create a target with the options of a configuration applied.
+fn merge_configuration_options(
+    nx_target: &NxTargetOptions,
+    config_options: &FxHashMap<String, JsonValue>,
+) -> NxTargetOptions {
+    let mut options = nx_target.options.clone().unwrap_or_default();
+    options.extend(config_options.clone());
+
+    NxTargetOptions {
+        cache: nx_target.cache,
+        command: nx_target.command.clone(),
+        configurations: None,
+        depends_on: nx_target.depends_on.clone(),
+        default_configuration: None,
+        executor: nx_target.executor.clone(),
+        inputs: nx_target.inputs.clone(),
+        options: Some(options),
+        outputs: nx_target.outputs.clone(),
+        unknown: nx_target.unknown.clone(),
+    }
+}
+
 // We'll parse these arguments back into an object using `yargs-parser`:
 // https://www.npmjs.com/package/yargs-parser
 fn migrate_options_to_args(options: &FxHashMap<String, JsonValue>) -> Vec<String> {
     let mut args = vec![];
 
-    for (key, value) in options {
+    // Sort the options, so that arguments are always in the same order
+    for (key, value) in options.iter().collect::<BTreeMap<_, _>>() {
         if matches!(value, JsonValue::Null) {
             continue;
         }
@@ -401,31 +1753,159 @@ fn migrate_noop_task(nx_target: &NxTargetOptions) -> AnyResult<PartialTaskConfig
 }
 
 // https://nx.dev/nx-api/nx/executors/run-commands
-fn migrate_run_commands_task(nx_target: &NxTargetOptions) -> AnyResult<PartialTaskConfig> {
+fn migrate_run_commands_task(
+    nx_target: &NxTargetOptions,
+    report: &mut MigrationReport,
+    file: &str,
+    pointer: &str,
+) -> AnyResult<(PartialTaskConfig, Vec<PartialTaskConfig>)> {
     let mut config = PartialTaskConfig {
         platform: Some(PlatformType::System),
         ..PartialTaskConfig::default()
     };
+    let mut parallel_commands = vec![];
 
     // https://nx.dev/nx-api/nx/executors/run-commands#options
     if let Some(options) = &nx_target.options {
+        let mut args = vec![];
+
+        match options.get("args") {
+            Some(JsonValue::String(value)) => {
+                args.extend(value.split_whitespace().map(|arg| arg.to_owned()));
+            }
+            Some(JsonValue::Array(values)) => {
+                args.extend(values.iter().map(convert_value_to_string_without_quotes));
+            }
+            _ => {}
+        };
+
+        if let Some(JsonValue::Array(values)) = options.get("__unparsed__") {
+            args.extend(values.iter().map(convert_value_to_string_without_quotes));
+        }
+
+        // Commands and whether arguments are forwarded to them
+        let mut commands = vec![];
+
         if let Some(JsonValue::String(command)) = options.get("command") {
-            config.command = Some(PartialTaskArgs::String(command.to_owned()));
-        } else if let Some(JsonValue::Array(commands)) = options.get("commands") {
-            config.command = Some(PartialTaskArgs::String(
+            commands.push((command.to_owned(), true));
+        } else if let Some(JsonValue::Array(list)) = options.get("commands") {
+            for item in list {
+                match item {
+                    // Other fields (prefix, color, etc) only affect the output
+                    JsonValue::Object(map) => {
+                        if let Some(JsonValue::String(command)) = map.get("command") {
+                            commands.push((
+                                command.to_owned(),
+                                !matches!(map.get("forwardAllArgs"), Some(JsonValue::Bool(false))),
+                            ));
+                        }
+                    }
+                    value => {
+                        commands.push((convert_value_to_string_without_quotes(value), true));
+                    }
+                };
+            }
+        }
+
+        // Paths are relative to the workspace root, which is also
+        // where commands run when no cwd is configured
+        let mut cwd_prefix = None;
+
+        if !options.contains_key("cwd") {
+            config
+                .options
+                .get_or_insert(PartialTaskOptionsConfig::default())
+                .run_from_workspace_root = Some(true);
+        } else if let Some(JsonValue::String(cwd)) = options.get("cwd") {
+            let cwd = replace_tokens(cwd, false);
+
+            match cwd.trim_start_matches("./").trim_end_matches('/') {
+                "$projectRoot" => {}
+                "" | "." | "$workspaceRoot" => {
+                    config
+                        .options
+                        .get_or_insert(PartialTaskOptionsConfig::default())
+                        .run_from_workspace_root = Some(true);
+                }
+                path => {
+                    config
+                        .options
+                        .get_or_insert(PartialTaskOptionsConfig::default())
+                        .run_from_workspace_root = Some(true);
+
+                    cwd_prefix = Some(format!("cd {path} && "));
+                }
+            };
+        }
+
+        let commands = commands
+            .into_iter()
+            .map(|(command, forward_args)| {
+                // Arguments are not forwarded when interpolated
+                let forward_args = forward_args && !args.is_empty() && !command.contains("{args.");
+
+                (
+                    interpolate_args(&replace_tokens(&command, false), &args),
+                    forward_args,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Commands run in parallel by default
+        let parallel = !matches!(options.get("parallel"), Some(JsonValue::Bool(false)));
+
+        if parallel && commands.len() > 1 {
+            for (command, forward_args) in commands {
+                parallel_commands.push(PartialTaskConfig {
+                    command: Some(PartialTaskArgs::String(format!(
+                        "{}{command}",
+                        cwd_prefix.as_deref().unwrap_or_default()
+                    ))),
+                    args: if forward_args {
+                        Some(PartialTaskArgs::List(args.clone()))
+                    } else {
+                        None
+                    },
+                    ..PartialTaskConfig::default()
+                });
+            }
+        } else if commands.len() == 1 {
+            let (command, forward_args) = &commands[0];
+
+            config.command = Some(PartialTaskArgs::String(format!(
+                "{}{command}",
+                cwd_prefix.as_deref().unwrap_or_default()
+            )));
+
+            if *forward_args {
+                config.args = Some(PartialTaskArgs::List(args.clone()));
+            }
+        } else if !commands.is_empty() {
+            // Arguments can only be appended to the last command,
+            // so inline them into each command instead
+            config.command = Some(PartialTaskArgs::String(format!(
+                "{}{}",
+                cwd_prefix.as_deref().unwrap_or_default(),
                 commands
                     .iter()
-                    .map(convert_value_to_string_without_quotes)
+                    .map(|(command, forward_args)| {
+                        if *forward_args {
+                            format!("{command} {}", args.join(" "))
+                        } else {
+                            command.to_owned()
+                        }
+                    })
                     .collect::<Vec<_>>()
-                    .join(" && "),
-            ));
+                    .join(" && ")
+            )));
         }
 
-        if let Some(JsonValue::String(cwd)) = options.get("cwd") {
-            config
-                .env
-                .get_or_insert(FxHashMap::default())
-                .insert("CWD".into(), cwd.to_owned());
+        if options.contains_key("readyWhen") {
+            report.unsupported(
+                file,
+                format!("{pointer}/options/readyWhen"),
+                "Tasks complete when all commands exit, readiness can't be detected from the output",
+            );
         }
 
         if let Some(JsonValue::Object(envs)) = options.get("env") {
@@ -445,13 +1925,58 @@ fn migrate_run_commands_task(nx_target: &NxTargetOptions) -> AnyResult<PartialTa
                 .get_or_insert(PartialTaskOptionsConfig::default())
                 .env_file = Some(TaskOptionEnvFile::File(FilePath::from_str(env_file)?));
         }
+
+        for key in options.keys() {
+            if !matches!(
+                key.as_str(),
+                "__unparsed__"
+                    | "args"
+                    | "command"
+                    | "commands"
+                    | "cwd"
+                    | "env"
+                    | "envFile"
+                    | "parallel"
+                    | "readyWhen"
+            ) {
+                report.unsupported(
+                    file,
+                    format!("{pointer}/options{}", create_pointer([key])),
+                    "Option is not supported by run-commands tasks",
+                );
+            }
+        }
     }
 
     if config.command.is_none() {
         config.command = Some(PartialTaskArgs::String("noop".into()));
     }
 
-    Ok(config)
+    Ok((config, parallel_commands))
+}
+
+/// Replace `{args.name}` in a command with the value of the `--name` argument.
+fn interpolate_args(command: &str, args: &[String]) -> String {
+    let mut result = command.to_owned();
+    let mut iter = args.iter().peekable();
+
+    while let Some(arg) = iter.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            continue;
+        };
+
+        let (name, value) = match name.split_once('=') {
+            Some((name, value)) => (name, value.to_owned()),
+            None => match iter.peek() {
+                Some(value) if !value.starts_with('-') => (name, iter.next().unwrap().to_owned()),
+                _ => (name, "true".to_owned()),
+            },
+        };
+
+        result = result.replace(&format!("{{args.{name}}}"), &value);
+    }
+
+    result
 }
 
 // https://nx.dev/nx-api/nx/executors/run-script
@@ -472,19 +1997,96 @@ fn migrate_run_script_task(
     Ok(config)
 }
 
+// Executors of official plugins that wrap a native command
+fn migrate_native_task(
+    nx_target: &NxTargetOptions,
+    native: &NxExecutor,
+    executor: &str,
+    report: &mut MigrationReport,
+    file: &str,
+    pointer: &str,
+) -> AnyResult<PartialTaskConfig> {
+    let mut config = PartialTaskConfig {
+        command: Some(PartialTaskArgs::String(native.command.to_owned())),
+        ..PartialTaskConfig::default()
+    };
+
+    let options = native.migrate_options(
+        nx_target.options.as_ref().unwrap_or(&FxHashMap::default()),
+        false,
+        report,
+        executor,
+        file,
+        &format!("{pointer}/options"),
+    )?;
+
+    if !options.args.is_empty() {
+        config.args = Some(PartialTaskArgs::List(options.args));
+    }
+
+    // Config files are inputs in addition to the project's files
+    if !options.inputs.is_empty() {
+        let mut inputs = options.inputs;
+        inputs.push(InputPath::ProjectGlob("**/*".into()));
+
+        config.inputs = Some(inputs);
+    }
+
+    if !options.outputs.is_empty() {
+        config.outputs = Some(options.outputs);
+    }
+
+    if native.local {
+        config.local = Some(true);
+    }
+
+    Ok(config)
+}
+
+/// Reserve the IDs of targets before migrating them, so that tasks created
+/// for parallel commands don't collide with targets migrated later.
+fn reserve_task_ids<'a>(
+    tasks: &mut BTreeMap<Id, PartialTaskConfig>,
+    names: impl IntoIterator<Item = &'a String>,
+) -> AnyResult<()> {
+    for name in names {
+        tasks.entry(create_id(name)?).or_default();
+    }
+
+    Ok(())
+}
+
+/// Migrate a target into the task of the provided ID. Additional tasks may be
+/// created for targets that run multiple commands in parallel. Inputs that
+/// reference dependency projects are returned, to be resolved later.
 fn migrate_task(
     nx_target: &NxTargetOptions,
+    task_id: &Id,
+    tasks: &mut BTreeMap<Id, PartialTaskConfig>,
     package_manager: &str,
-) -> AnyResult<PartialTaskConfig> {
+    report: &mut MigrationReport,
+    file: &str,
+    pointer: &str,
+) -> AnyResult<Vec<NxDependencyInput>> {
     let mut inject_args = false;
+    let mut parallel_commands = vec![];
+
+    for (key, value) in &nx_target.unknown {
+        report_unknown_setting(report, file, pointer, key, value);
+    }
 
     let mut config = if let Some(executor) = &nx_target.executor {
         if executor == "nx:noop" {
             migrate_noop_task(nx_target)?
         } else if executor == "nx:run-commands" {
-            migrate_run_commands_task(nx_target)?
+            let (config, commands) = migrate_run_commands_task(nx_target, report, file, pointer)?;
+
+            parallel_commands = commands;
+            config
         } else if executor == "nx:run-script" {
             migrate_run_script_task(nx_target, package_manager)?
+        } else if let Some(native) = find_executor(executor) {
+            migrate_native_task(nx_target, &native, executor, report, file, pointer)?
         } else {
             let mut parts = executor.splitn(2, ':');
             let mut package = parts.next().unwrap_or_default();
@@ -494,14 +2096,24 @@ fn migrate_task(
                 package = &package[index + 1..];
             }
 
+            let command = if package == target {
+                target.to_owned()
+            } else {
+                format!("{package} {target}")
+            };
+
             inject_args = true;
 
+            report.approximated(
+                file,
+                format!("{pointer}/executor"),
+                format!(
+                    "Executor was converted to the command `{command}` with options as arguments"
+                ),
+            );
+
             PartialTaskConfig {
-                command: Some(PartialTaskArgs::String(if package == target {
-                    target.to_owned()
-                } else {
-                    format!("{package} {target}")
-                })),
+                command: Some(PartialTaskArgs::String(command)),
                 ..PartialTaskConfig::default()
             }
         }
@@ -526,14 +2138,22 @@ fn migrate_task(
     if let Some(depends_on) = &nx_target.depends_on {
         let mut deps: Vec<Target> = vec![];
 
-        for dep in depends_on {
+        for (index, dep) in depends_on.iter().enumerate() {
             match dep {
                 NxDependsOn::Object {
                     dependencies,
                     target,
+                    params,
                     projects,
-                    ..
                 } => {
+                    if params.as_deref() == Some("forward") {
+                        report.approximated(
+                            file,
+                            format!("{pointer}/dependsOn/{index}/params"),
+                            "Arguments passed on the command line are not forwarded to dependencies, only configurations are linked to the matching configuration tasks",
+                        );
+                    }
+
                     if let Some(projects) = projects {
                         match projects {
                             StringOrList::List(ids) => {
@@ -593,11 +2213,26 @@ fn migrate_task(
     let mut inputs = vec![];
 
     if let Some(raw_inputs) = &nx_target.inputs {
-        inputs.extend(migrate_inputs(raw_inputs, false)?);
+        inputs.extend(migrate_inputs(
+            raw_inputs,
+            false,
+            report,
+            file,
+            &format!("{pointer}/inputs"),
+        )?);
     }
 
     if !inputs.is_empty() {
-        config.inputs = Some(inputs);
+        match &mut config.inputs {
+            // Replace the project's files with the explicit inputs
+            Some(executor_inputs) => {
+                executor_inputs.retain(|input| input != &InputPath::ProjectGlob("**/*".into()));
+                executor_inputs.extend(inputs);
+            }
+            None => {
+                config.inputs = Some(inputs);
+            }
+        };
     }
 
     // Outputs
@@ -606,6 +2241,21 @@ fn migrate_task(
         let mut outputs = vec![];
 
         for output in raw_outputs {
+            // {options.outputPath}
+            if let Some(option) = output
+                .strip_prefix("{options.")
+                .and_then(|option| option.strip_suffix('}'))
+            {
+                if let Some(JsonValue::String(value)) = nx_target
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.get(option))
+                {
+                    outputs.push(OutputPath::from_str(&to_source_path(value))?);
+                    continue;
+                }
+            }
+
             outputs.push(OutputPath::from_str(&replace_tokens(output, true))?);
         }
 
@@ -621,5 +2271,84 @@ fn migrate_task(
             .cache = nx_target.cache;
     }
 
-    Ok(config)
+    // Each parallel command is a sibling task with the same settings,
+    // and the original task depends on all of them. Outputs are only
+    // defined on the original task, as any of the commands may create them.
+    let mut input_task_ids = vec![];
+
+    if !parallel_commands.is_empty() {
+        let mut deps = vec![];
+
+        for (index, command) in parallel_commands.into_iter().enumerate() {
+            let mut command_task_id = create_id(format!("{task_id}-{}", index + 1))?;
+
+            // Don't overwrite targets or existing tasks of the same name
+            if tasks.contains_key(&command_task_id) {
+                let fallback_task_id = create_id(format!("{task_id}-command-{}", index + 1))?;
+
+                if tasks.contains_key(&fallback_task_id) {
+                    return Err(anyhow!(
+                        "Unable to create a task for command {} of <id>{task_id}</id> in <file>{file}</file>, as tasks <id>{command_task_id}</id> and <id>{fallback_task_id}</id> already exist.",
+                        index + 1
+                    ));
+                }
+
+                report.approximated(
+                    file,
+                    format!("{pointer}/options/commands/{index}"),
+                    format!(
+                        "Task {command_task_id} already exists, so this command runs in task {fallback_task_id}"
+                    ),
+                );
+
+                command_task_id = fallback_task_id;
+            }
+
+            deps.push(PartialTaskDependency::Target(
+                Target::new_self(&command_task_id).map_err(map_miette_error)?,
+            ));
+
+            input_task_ids.push(command_task_id.clone());
+
+            tasks.insert(
+                command_task_id,
+                PartialTaskConfig {
+                    command: command.command,
+                    args: command.args,
+                    outputs: None,
+                    ..config.clone()
+                },
+            );
+        }
+
+        config = PartialTaskConfig {
+            command: Some(PartialTaskArgs::String("noop".into())),
+            deps: Some(deps),
+            outputs: config.outputs,
+            local: config.local,
+            ..PartialTaskConfig::default()
+        };
+    } else {
+        input_task_ids.push(task_id.to_owned());
+    }
+
+    tasks.insert(task_id.to_owned(), config);
+
+    let mut dependency_inputs = vec![];
+
+    for (index, input) in nx_target.inputs.iter().flatten().enumerate() {
+        if is_dependency_input(input) {
+            for input_task_id in &input_task_ids {
+                dependency_inputs.push(NxDependencyInput {
+                    project_source: None,
+                    task_id: input_task_id.to_owned(),
+                    file: file.to_owned(),
+                    pointer: format!("{pointer}/inputs/{index}"),
+                    input: input.to_owned(),
+                });
+            }
+        }
+    }
+
+    Ok(dependency_inputs)
 }
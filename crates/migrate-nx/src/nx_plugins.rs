@@ -0,0 +1,239 @@
+// https://nx.dev/concepts/inferred-tasks
+
+use crate::nx_json::NxPlugin;
+use rustc_hash::FxHashMap;
+use starbase_utils::json::JsonValue;
+
+/// A task that a plugin infers for projects with a matching config file.
+pub struct NxInferredTarget {
+    /// Plugin option that customizes the target name.
+    pub name_option: &'static str,
+    pub default_name: &'static str,
+    pub command: &'static str,
+    /// Long-running processes, like development servers.
+    pub local: bool,
+    /// Outputs that are always created, relative to the project.
+    pub outputs: &'static [&'static str],
+    /// Outputs are read from the config file, which can't be evaluated.
+    pub outputs_from_config: bool,
+    /// Config files in the project root that enable the target.
+    pub config_files: &'static [&'static str],
+    /// Text that the config file must contain, e.g. a `test` block.
+    pub config_contains: Option<&'static str>,
+}
+
+pub struct NxPluginEntry {
+    pub name: String,
+    pub targets: &'static [NxInferredTarget],
+    pub options: FxHashMap<String, JsonValue>,
+    /// Globs of config files that the plugin applies to.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl NxPluginEntry {
+    pub fn get_target_name(&self, target: &NxInferredTarget) -> String {
+        match self.options.get(target.name_option) {
+            Some(JsonValue::String(name)) => name.to_owned(),
+            _ => target.default_name.to_owned(),
+        }
+    }
+}
+
+const CYPRESS_CONFIGS: &[&str] = &[
+    "cypress.config.js",
+    "cypress.config.ts",
+    "cypress.config.mjs",
+    "cypress.config.cjs",
+];
+
+const ESLINT_CONFIGS: &[&str] = &[
+    "eslint.config.js",
+    "eslint.config.mjs",
+    "eslint.config.cjs",
+    ".eslintrc",
+    ".eslintrc.json",
+    ".eslintrc.js",
+    ".eslintrc.cjs",
+    ".eslintrc.yaml",
+    ".eslintrc.yml",
+];
+
+const JEST_CONFIGS: &[&str] = &[
+    "jest.config.js",
+    "jest.config.ts",
+    "jest.config.mjs",
+    "jest.config.mts",
+    "jest.config.cjs",
+    "jest.config.cts",
+];
+
+const NEXT_CONFIGS: &[&str] = &["next.config.js", "next.config.mjs", "next.config.cjs"];
+
+const PLAYWRIGHT_CONFIGS: &[&str] = &[
+    "playwright.config.js",
+    "playwright.config.ts",
+    "playwright.config.mjs",
+    "playwright.config.cjs",
+];
+
+const VITE_CONFIGS: &[&str] = &[
+    "vite.config.js",
+    "vite.config.ts",
+    "vite.config.mjs",
+    "vite.config.mts",
+    "vite.config.cjs",
+    "vite.config.cts",
+];
+
+const VITEST_CONFIGS: &[&str] = &[
+    "vitest.config.js",
+    "vitest.config.ts",
+    "vitest.config.mjs",
+    "vitest.config.mts",
+    "vitest.config.cjs",
+    "vitest.config.cts",
+    "vite.config.js",
+    "vite.config.ts",
+    "vite.config.mjs",
+    "vite.config.mts",
+    "vite.config.cjs",
+    "vite.config.cts",
+];
+
+const WEBPACK_CONFIGS: &[&str] = &[
+    "webpack.config.js",
+    "webpack.config.ts",
+    "webpack.config.mjs",
+    "webpack.config.cjs",
+];
+
+const fn target(
+    name_option: &'static str,
+    default_name: &'static str,
+    command: &'static str,
+    config_files: &'static [&'static str],
+) -> NxInferredTarget {
+    NxInferredTarget {
+        name_option,
+        default_name,
+        command,
+        local: false,
+        outputs: &[],
+        outputs_from_config: false,
+        config_files,
+        config_contains: None,
+    }
+}
+
+const fn local_target(
+    name_option: &'static str,
+    default_name: &'static str,
+    command: &'static str,
+    config_files: &'static [&'static str],
+) -> NxInferredTarget {
+    NxInferredTarget {
+        local: true,
+        ..target(name_option, default_name, command, config_files)
+    }
+}
+
+const fn build_target(
+    name_option: &'static str,
+    default_name: &'static str,
+    command: &'static str,
+    config_files: &'static [&'static str],
+) -> NxInferredTarget {
+    NxInferredTarget {
+        outputs_from_config: true,
+        ..target(name_option, default_name, command, config_files)
+    }
+}
+
+// https://nx.dev/nx-api/cypress#cypressplugin
+const CYPRESS_TARGETS: &[NxInferredTarget] =
+    &[target("targetName", "e2e", "cypress run", CYPRESS_CONFIGS)];
+
+// https://nx.dev/nx-api/eslint#nxeslintplugin
+const ESLINT_TARGETS: &[NxInferredTarget] =
+    &[target("targetName", "lint", "eslint .", ESLINT_CONFIGS)];
+
+// https://nx.dev/nx-api/jest#nxjestplugin
+const JEST_TARGETS: &[NxInferredTarget] = &[target("targetName", "test", "jest", JEST_CONFIGS)];
+
+// https://nx.dev/nx-api/next#nxnextplugin
+const NEXT_TARGETS: &[NxInferredTarget] = &[
+    NxInferredTarget {
+        outputs: &[".next/**/*"],
+        ..target("buildTargetName", "build", "next build", NEXT_CONFIGS)
+    },
+    local_target("devTargetName", "dev", "next dev", NEXT_CONFIGS),
+    local_target("startTargetName", "start", "next start", NEXT_CONFIGS),
+];
+
+// https://nx.dev/nx-api/playwright#nxplaywrightplugin
+const PLAYWRIGHT_TARGETS: &[NxInferredTarget] = &[target(
+    "targetName",
+    "e2e",
+    "playwright test",
+    PLAYWRIGHT_CONFIGS,
+)];
+
+// https://nx.dev/nx-api/vite#nxviteplugin
+const VITE_TARGETS: &[NxInferredTarget] = &[
+    build_target("buildTargetName", "build", "vite build", VITE_CONFIGS),
+    local_target("serveTargetName", "serve", "vite", VITE_CONFIGS),
+    local_target("previewTargetName", "preview", "vite preview", VITE_CONFIGS),
+    NxInferredTarget {
+        config_contains: Some("test"),
+        ..target("testTargetName", "test", "vitest run", VITEST_CONFIGS)
+    },
+];
+
+// https://nx.dev/nx-api/webpack#nxwebpackplugin
+const WEBPACK_TARGETS: &[NxInferredTarget] = &[
+    build_target("buildTargetName", "build", "webpack build", WEBPACK_CONFIGS),
+    local_target("serveTargetName", "serve", "webpack serve", WEBPACK_CONFIGS),
+    local_target(
+        "previewTargetName",
+        "preview",
+        "webpack serve --mode production",
+        WEBPACK_CONFIGS,
+    ),
+];
+
+pub fn find_plugin(plugin: NxPlugin) -> Result<NxPluginEntry, String> {
+    let (name, options, include, exclude) = match plugin {
+        NxPlugin::Object {
+            plugin,
+            options,
+            include,
+            exclude,
+        } => (
+            plugin,
+            options.unwrap_or_default(),
+            include.unwrap_or_default(),
+            exclude.unwrap_or_default(),
+        ),
+        NxPlugin::String(plugin) => (plugin, FxHashMap::default(), vec![], vec![]),
+    };
+
+    let targets = match name.as_str() {
+        "@nx/cypress/plugin" => CYPRESS_TARGETS,
+        "@nx/eslint/plugin" => ESLINT_TARGETS,
+        "@nx/jest/plugin" => JEST_TARGETS,
+        "@nx/next/plugin" => NEXT_TARGETS,
+        "@nx/playwright/plugin" => PLAYWRIGHT_TARGETS,
+        "@nx/vite/plugin" => VITE_TARGETS,
+        "@nx/webpack/plugin" => WEBPACK_TARGETS,
+        _ => return Err(name),
+    };
+
+    Ok(NxPluginEntry {
+        name,
+        targets,
+        options,
+        include,
+        exclude,
+    })
+}
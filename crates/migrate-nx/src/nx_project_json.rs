@@ -3,7 +3,8 @@
 use crate::nx_json::{NxNamedInputs, NxTargetOptions};
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
-use std::collections::HashMap;
+use starbase_utils::json::JsonValue;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,14 +14,24 @@ pub struct NxProjectJson {
     pub name: Option<String>,
     pub named_inputs: Option<NxNamedInputs>,
     pub project_type: Option<String>,
-    // pub root: Option<PathBuf>,
-    // pub source_root: Option<PathBuf>,
+    pub root: Option<String>,
+    pub source_root: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(alias = "architect")]
     pub targets: Option<FxHashMap<String, NxTargetOptions>>,
+    // Not supported:
+    // generators, release, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
 }
 
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageJsonWithNx {
+    pub dependencies: Option<BTreeMap<String, JsonValue>>,
+    pub dev_dependencies: Option<BTreeMap<String, JsonValue>>,
+    pub name: Option<String>,
     pub nx: Option<NxProjectJson>,
+    pub optional_dependencies: Option<BTreeMap<String, JsonValue>>,
+    pub peer_dependencies: Option<BTreeMap<String, JsonValue>>,
 }
@@ -44,6 +44,172 @@ mod migrate_nx {
         assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_run_commands_options() {
+        let sandbox = create_sandbox("run-commands");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_nx_plugin_executors() {
+        let sandbox = create_sandbox("nx-plugin-executors");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_files_in_dry_run() {
+        let sandbox = create_sandbox("root");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("nx.json").exists());
+        assert!(sandbox.path().join("workspace.json").exists());
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+    }
+
+    // The host prints logs directly to stdout, so run the dry run in a child
+    // process of this test binary to capture them.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn run_dry_run_in_sandbox() {
+        let Ok(root) = std::env::var("DRY_RUN_SANDBOX") else {
+            return;
+        };
+        let plugin = create_extension("test", std::path::Path::new(&root));
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(std::path::Path::new(&root)),
+            })
+            .await;
+    }
+
+    #[test]
+    fn prints_a_diff_in_dry_run() {
+        let sandbox = create_sandbox("root");
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "migrate_nx::run_dry_run_in_sandbox",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env("DRY_RUN_SANDBOX", sandbox.path())
+            .env("NO_COLOR", "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains("--- /dev/null\n+++ .moon/workspace.yml"));
+        assert!(stdout.contains("--- /dev/null\n+++ .moon/tasks/node.yml"));
+        assert!(stdout.contains("+projects:"));
+        assert!(stdout.contains("Would remove nx.json"));
+        assert!(stdout.contains("Would remove workspace.json"));
+        assert!(stdout.contains("Dry run complete, no files were changed!"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_a_report_of_unsupported_settings() {
+        let sandbox = create_sandbox("root");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_restore_from_backup() {
+        let sandbox = create_sandbox("projects");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("bar/project.json").exists());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("nx.json").exists());
+        assert!(sandbox.path().join("bar/project.json").exists());
+        assert!(sandbox.path().join("baz/project.json").exists());
+        assert!(sandbox.path().join("foo/project.json").exists());
+        assert!(!sandbox.path().join("bar/moon.yml").exists());
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn removes_the_report_when_restoring() {
+        let sandbox = create_sandbox("root");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("nx.json").exists());
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
     mod nx_json {
         use super::*;
 
@@ -115,6 +281,57 @@ mod migrate_nx {
                 fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap()
             );
         }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn converts_angular_cli_workspace() {
+            let sandbox = create_sandbox("angular-cli");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            // Required by the Angular CLI
+            assert!(sandbox.path().join("angular.json").exists());
+
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap()
+            );
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("projects/ui-lib/moon.yml")).unwrap()
+            );
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn converts_inline_projects() {
+            let sandbox = create_sandbox("workspace-inline");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert!(!sandbox.path().join("workspace.json").exists());
+
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap()
+            );
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/app/moon.yml")).unwrap());
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/e2e/moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("libs/shared/moon.yml")).unwrap()
+            );
+        }
     }
 
     mod projects {
@@ -145,6 +362,141 @@ mod migrate_nx {
             assert_snapshot!(fs::read_to_string(sandbox.path().join("foo/moon.yml")).unwrap());
         }
 
+        #[tokio::test(flavor = "multi_thread")]
+        async fn resolves_implicit_dep_globs_and_negations() {
+            let sandbox = create_sandbox("project-implicit-deps");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/app/moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        #[should_panic(
+            expected = "Implicit dependency missing-* in app/project.json does not match any project."
+        )]
+        async fn errors_if_implicit_dep_matches_nothing() {
+            let sandbox = create_sandbox("project-implicit-deps-missing");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        #[should_panic(expected = "app/moon.yml: missing:build in task build")]
+        async fn errors_if_task_dep_references_unknown_project() {
+            let sandbox = create_sandbox("project-deps-dangling");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert!(!sandbox.path().join("app/moon.yml").exists());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        #[should_panic(
+            expected = "app/moon.yml: Task build depends on lib:compile, which does not exist."
+        )]
+        async fn errors_if_task_dep_references_unknown_task() {
+            let sandbox = create_sandbox("project-deps-missing-task");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        #[should_panic(expected = "a command is required")]
+        async fn errors_if_generated_config_fails_schema_validation() {
+            let sandbox = create_sandbox("error-invalid-config");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn resolves_task_deps_from_cached_project_graph() {
+            let sandbox = create_sandbox("project-deps-cached");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("app/moon.yml")).unwrap());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn ignores_cached_project_graph_that_cant_be_parsed() {
+            let sandbox = create_sandbox("project-deps-cache-invalid");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert!(sandbox.path().join("app/moon.yml").exists());
+            assert!(sandbox.path().join("lib/moon.yml").exists());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn infers_tasks_from_plugins() {
+            let sandbox = create_sandbox("plugins");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/web/moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("packages/lib/moon.yml")).unwrap()
+            );
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("packages/legacy/moon.yml")).unwrap()
+            );
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
         #[tokio::test(flavor = "multi_thread")]
         async fn converts_name_and_implicit_deps() {
             let sandbox = create_sandbox("project-name-deps");
@@ -204,6 +556,80 @@ mod migrate_nx {
             assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
         }
 
+        #[tokio::test(flavor = "multi_thread")]
+        async fn links_configuration_deps() {
+            let sandbox = create_sandbox("project-configurations");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("app/moon.yml")).unwrap());
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("lib/moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn merges_executor_target_defaults() {
+            let sandbox = create_sandbox("project-executor-defaults");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap()
+            );
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("app/moon.yml")).unwrap());
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("lib/moon.yml")).unwrap());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn merges_default_configuration() {
+            let sandbox = create_sandbox("project-default-configuration");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn converts_dependency_inputs() {
+            let sandbox = create_sandbox("project-dep-inputs");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("moon.yml")).unwrap());
+            assert_snapshot!(
+                fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap()
+            );
+        }
+
         #[tokio::test(flavor = "multi_thread")]
         async fn converts_targets() {
             let sandbox = create_sandbox("project-targets");
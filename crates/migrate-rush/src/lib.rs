@@ -0,0 +1,7 @@
+#[cfg(feature = "wasm")]
+mod migrate_rush_ext;
+mod rush_json;
+mod rush_migrator;
+
+#[cfg(feature = "wasm")]
+pub use migrate_rush_ext::*;
@@ -0,0 +1,79 @@
+use crate::rush_migrator::RushMigrator;
+use extism_pdk::*;
+use moon_extension_common::migrator::{run_migration, TaskConflictStrategy};
+use moon_pdk::*;
+use starbase_utils::json;
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
+
+#[plugin_fn]
+pub fn register_extension(
+    Json(_): Json<ExtensionMetadataInput>,
+) -> FnResult<Json<ExtensionMetadataOutput>> {
+    Ok(Json(ExtensionMetadataOutput {
+        name: "Migrate Rush".into(),
+        description: Some("Migrate a Rush repository to moon by converting <file>rush.json</file>, commands, and project operation settings into moon configuration files.".into()),
+        plugin_version: env!("CARGO_PKG_VERSION").into(),
+        config_schema: None,
+    }))
+}
+
+#[derive(Args)]
+pub struct MigrateRushExtensionArgs {
+    #[arg(long)]
+    pub bun: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
+}
+
+#[plugin_fn]
+pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
+    let args = parse_args::<MigrateRushExtensionArgs>(&input.args)?;
+    let workspace_root = &input.context.workspace_root;
+
+    run_migration(workspace_root, "Rush", args.dry_run, args.restore, || {
+        let mut migrator = RushMigrator::new(&input.context, args.bun)?;
+
+        // Load commands first, as they determine the tasks of each project
+        let root_config_path = workspace_root.join("rush.json");
+
+        if !root_config_path.exists() {
+            return Err(plugin_err!(
+                "Unable to migrate, no <file>rush.json</file> found in the workspace root."
+            ));
+        }
+
+        let command_line_path = workspace_root.join("common/config/rush/command-line.json");
+
+        if command_line_path.exists() {
+            host_log!(
+                stdout,
+                "Migrating commands <file>common/config/rush/command-line.json</file>"
+            );
+
+            migrator.migrate_command_line(json::read_file(&command_line_path)?)?;
+            migrator.inner.remove_file(command_line_path);
+        }
+
+        // Then the root config, which migrates each project
+        host_log!(stdout, "Migrating root config <file>rush.json</file>");
+
+        migrator.migrate_root_config(json::read_file(&root_config_path)?)?;
+        migrator.inner.remove_file(root_config_path);
+
+        // Resolve migrated tasks that collide with existing moon tasks
+        migrator.inner.resolve_task_conflicts(args.on_conflict)?;
+
+        Ok((migrator.inner, migrator.report))
+    })
+}
@@ -0,0 +1,96 @@
+// https://rushjs.io/pages/configs/rush_json/
+// https://rushjs.io/pages/configs/command_line_json/
+// https://rushjs.io/pages/configs/rush-project_json/
+
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use starbase_utils::json::JsonValue;
+use std::collections::BTreeMap;
+
+// rush.json
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushProject {
+    pub package_name: String,
+    pub project_folder: String,
+    pub tags: Option<Vec<String>>,
+    // Not supported:
+    // shouldPublish, versionPolicyName, decoupledLocalDependencies, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushJson {
+    pub npm_version: Option<String>,
+    pub pnpm_version: Option<String>,
+    pub projects: Vec<RushProject>,
+    pub yarn_version: Option<String>,
+}
+
+// common/config/rush/command-line.json
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushCommand {
+    pub command_kind: String,
+    pub ignore_dependency_order: Option<bool>,
+    pub name: String,
+    pub phases: Option<Vec<String>>,
+    // Not supported:
+    // shellCommand, watchOptions, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct RushPhaseDependencies {
+    #[serde(rename = "self")]
+    pub self_: Option<Vec<String>>,
+    pub upstream: Option<Vec<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushPhase {
+    pub dependencies: Option<RushPhaseDependencies>,
+    pub name: String,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushCommandLineJson {
+    pub commands: Option<Vec<RushCommand>>,
+    pub parameters: Option<Vec<JsonValue>>,
+    pub phases: Option<Vec<RushPhase>>,
+}
+
+// config/rush-project.json
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushOperationSettings {
+    pub disable_build_cache_for_operation: Option<bool>,
+    pub operation_name: String,
+    pub output_folder_names: Option<Vec<String>>,
+    // Not supported:
+    // dependsOnEnvVars, dependsOnAdditionalFiles, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RushProjectJson {
+    pub disable_build_cache_for_project: Option<bool>,
+    pub operation_settings: Option<Vec<RushOperationSettings>>,
+    // Not supported:
+    // incrementalBuildIgnoredGlobs, extends, etc
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, JsonValue>,
+}
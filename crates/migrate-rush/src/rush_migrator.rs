@@ -0,0 +1,403 @@
+use crate::rush_json::*;
+use moon_common::Id;
+use moon_config::{
+    OutputPath, PartialTaskArgs, PartialTaskConfig, PartialTaskDependency,
+    PartialTaskOptionsConfig, PartialWorkspaceProjects, PlatformType,
+};
+use moon_extension_common::migrator::*;
+use moon_extension_common::package_json::*;
+use moon_extension_common::report::*;
+use moon_pdk::{map_miette_error, AnyResult, MoonContext};
+use moon_target::Target;
+use rustc_hash::FxHashMap;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+
+pub struct RushOperation {
+    pub task_id: Id,
+    /// Operations within the same project that must run first.
+    pub self_deps: Vec<String>,
+    /// Operations within dependency projects that must run first.
+    pub upstream_deps: Vec<String>,
+}
+
+pub struct RushMigrator {
+    pub inner: Migrator,
+    /// Operations (script names) that are ran by Rush commands.
+    pub operations: BTreeMap<String, RushOperation>,
+    pub package_manager: String,
+    pub report: MigrationReport,
+}
+
+impl RushMigrator {
+    pub fn new(context: &MoonContext, bun: bool) -> AnyResult<Self> {
+        let mut migrator = Migrator::new(&context.workspace_root)?;
+
+        if bun {
+            migrator.platform = PlatformType::Bun;
+        }
+
+        // The `build` command is built-in and ran in topological order
+        let mut operations = BTreeMap::default();
+
+        operations.insert(
+            "build".into(),
+            RushOperation {
+                task_id: create_id("build")?,
+                self_deps: vec![],
+                upstream_deps: vec!["build".into()],
+            },
+        );
+
+        Ok(Self {
+            package_manager: migrator.detect_package_manager(),
+            inner: migrator,
+            operations,
+            report: MigrationReport::default(),
+        })
+    }
+
+    pub fn migrate_command_line(
+        &mut self,
+        command_line_json: RushCommandLineJson,
+    ) -> AnyResult<()> {
+        let file = "common/config/rush/command-line.json";
+
+        for (index, command) in command_line_json
+            .commands
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let index = index.to_string();
+
+            match command.command_kind.as_str() {
+                "bulk" => {
+                    self.operations.insert(
+                        command.name.clone(),
+                        RushOperation {
+                            task_id: create_id(&command.name)?,
+                            self_deps: vec![],
+                            upstream_deps: if command.ignore_dependency_order == Some(true) {
+                                vec![]
+                            } else {
+                                vec![command.name.clone()]
+                            },
+                        },
+                    );
+                }
+                "phased" => {
+                    // Replaces the built-in bulk command of the same name
+                    self.operations.remove(&command.name);
+
+                    self.report.approximated(
+                        file,
+                        create_pointer(["commands", &index]),
+                        "Phased command was converted to a task for each of its phases",
+                    );
+                }
+                "global" => {
+                    self.report.unsupported(
+                        file,
+                        create_pointer(["commands", &index]),
+                        "Global commands are not supported, use a task in a root-level project instead",
+                    );
+
+                    continue;
+                }
+                kind => {
+                    self.report.unsupported(
+                        file,
+                        create_pointer(["commands", &index, "commandKind"]),
+                        format!("Command kind {kind} is not supported"),
+                    );
+
+                    continue;
+                }
+            };
+
+            for key in command.unknown.keys() {
+                match key.as_str() {
+                    "allowWarningsInSuccessfulBuild"
+                    | "description"
+                    | "enableParallelism"
+                    | "ignoreMissingScript"
+                    | "incremental"
+                    | "safeForSimultaneousRushProcesses"
+                    | "summary" => continue,
+                    _ => {
+                        self.report.unsupported(
+                            file,
+                            create_pointer(["commands", &index, key]),
+                            "Setting is not supported",
+                        );
+                    }
+                };
+            }
+        }
+
+        if command_line_json
+            .parameters
+            .is_some_and(|parameters| !parameters.is_empty())
+        {
+            self.report.unsupported(
+                file,
+                "/parameters",
+                "Custom parameters are not supported, pass arguments to tasks after -- instead",
+            );
+        }
+
+        for (index, phase) in command_line_json
+            .phases
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let dependencies = phase.dependencies.unwrap_or_default();
+            let task_id = create_id(phase.name.strip_prefix("_phase:").unwrap_or(&phase.name))?;
+
+            self.operations.insert(
+                phase.name.clone(),
+                RushOperation {
+                    task_id,
+                    self_deps: dependencies.self_.unwrap_or_default(),
+                    upstream_deps: dependencies.upstream.unwrap_or_default(),
+                },
+            );
+
+            for key in phase.unknown.keys() {
+                match key.as_str() {
+                    "allowWarningsOnSuccess" | "ignoreMissingScript" | "missingScriptBehavior" => {
+                        continue
+                    }
+                    _ => {
+                        self.report.unsupported(
+                            file,
+                            create_pointer(["phases", &index.to_string(), key]),
+                            "Setting is not supported",
+                        );
+                    }
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn migrate_root_config(&mut self, rush_json: RushJson) -> AnyResult<()> {
+        // Package manager
+        if !matches!(self.inner.platform, PlatformType::Bun) {
+            if rush_json.pnpm_version.is_some() {
+                self.package_manager = "pnpm".into();
+            } else if rush_json.yarn_version.is_some() {
+                self.package_manager = "yarn".into();
+            } else if rush_json.npm_version.is_some() {
+                self.package_manager = "npm".into();
+            }
+        }
+
+        // Projects
+        let mut sources = FxHashMap::default();
+
+        for project in &rush_json.projects {
+            sources.insert(
+                create_id(&project.package_name)?,
+                project.project_folder.trim_end_matches('/').to_owned(),
+            );
+        }
+
+        let workspace_config = self.inner.load_workspace_config()?;
+
+        if workspace_config.projects.is_none() {
+            workspace_config.projects = Some(PartialWorkspaceProjects::Sources(sources));
+        }
+
+        for (index, project) in rush_json.projects.into_iter().enumerate() {
+            for key in project.unknown.keys() {
+                let reason = match key.as_str() {
+                    "publishFolder" | "shouldPublish" | "versionPolicyName" => {
+                        "Versioning and publishing packages is not supported"
+                    }
+                    _ => "Setting is not supported",
+                };
+
+                self.report.unsupported(
+                    "rush.json",
+                    create_pointer(["projects", &index.to_string(), key]),
+                    reason,
+                );
+            }
+
+            self.migrate_project(project)?;
+        }
+
+        Ok(())
+    }
+
+    fn migrate_project(&mut self, project: RushProject) -> AnyResult<()> {
+        let project_source = project.project_folder.trim_end_matches('/');
+        let project_root = self.inner.root.join(project_source);
+
+        // Scripts that Rush would run for each operation
+        let package_json_path = project_root.join("package.json");
+        let package_json: PackageJson = if package_json_path.exists() {
+            json::read_file(&package_json_path)?
+        } else {
+            PackageJson::default()
+        };
+        let scripts = package_json.scripts.unwrap_or_default();
+
+        let mut tasks = BTreeMap::default();
+
+        for (name, operation) in &self.operations {
+            if !scripts.contains_key(name) {
+                continue;
+            }
+
+            let mut deps = vec![];
+
+            for dep in &operation.upstream_deps {
+                deps.push(PartialTaskDependency::Target(
+                    Target::parse(format!("^:{}", self.get_task_id(dep)?).as_str())
+                        .map_err(map_miette_error)?,
+                ));
+            }
+
+            // Only depend on tasks that will exist in this project
+            for dep in &operation.self_deps {
+                if scripts.contains_key(dep) {
+                    deps.push(PartialTaskDependency::Target(
+                        Target::new_self(self.get_task_id(dep)?).map_err(map_miette_error)?,
+                    ));
+                }
+            }
+
+            tasks.insert(
+                operation.task_id.clone(),
+                PartialTaskConfig {
+                    command: Some(PartialTaskArgs::String(format!(
+                        "{} run {name}",
+                        self.package_manager
+                    ))),
+                    deps: if deps.is_empty() { None } else { Some(deps) },
+                    ..PartialTaskConfig::default()
+                },
+            );
+        }
+
+        // Operation settings
+        let rush_project_path = project_root.join("config/rush-project.json");
+
+        if rush_project_path.exists() {
+            let rush_project: RushProjectJson = json::read_file(&rush_project_path)?;
+            let file = format!("{project_source}/config/rush-project.json");
+
+            for (index, settings) in rush_project
+                .operation_settings
+                .unwrap_or_default()
+                .into_iter()
+                .enumerate()
+            {
+                let index = index.to_string();
+                let task = match self.operations.get(&settings.operation_name) {
+                    Some(operation) => tasks.get_mut(&operation.task_id),
+                    None => None,
+                };
+
+                let Some(task) = task else {
+                    self.report.unsupported(
+                        &file,
+                        create_pointer(["operationSettings", &index]),
+                        "Operation does not match a script that is ran by a command",
+                    );
+
+                    continue;
+                };
+
+                if let Some(folders) = settings.output_folder_names {
+                    let mut outputs = vec![];
+
+                    for folder in folders {
+                        outputs.push(OutputPath::ProjectGlob(format!(
+                            "{}/**/*",
+                            folder.trim_end_matches('/')
+                        )));
+                    }
+
+                    if !outputs.is_empty() {
+                        task.outputs = Some(outputs);
+                    }
+                }
+
+                if settings.disable_build_cache_for_operation == Some(true) {
+                    task.options
+                        .get_or_insert(PartialTaskOptionsConfig::default())
+                        .cache = Some(false);
+                }
+
+                for key in settings.unknown.keys() {
+                    self.report.unsupported(
+                        &file,
+                        create_pointer(["operationSettings", &index, key]),
+                        "Setting is not supported",
+                    );
+                }
+            }
+
+            if rush_project.disable_build_cache_for_project == Some(true) {
+                for task in tasks.values_mut() {
+                    task.options
+                        .get_or_insert(PartialTaskOptionsConfig::default())
+                        .cache = Some(false);
+                }
+            }
+
+            for key in rush_project.unknown.keys() {
+                let reason = match key.as_str() {
+                    "$schema" => continue,
+                    "incrementalBuildIgnoredGlobs" => {
+                        "Use task inputs to control which files affect the cache instead"
+                    }
+                    _ => "Setting is not supported",
+                };
+
+                self.report
+                    .unsupported(&file, create_pointer([key]), reason);
+            }
+
+            self.inner.remove_file(rush_project_path);
+        }
+
+        // Project config
+        let project_config = self.inner.load_project_config(project_source)?;
+
+        if let Some(tags) = project.tags {
+            for tag in tags {
+                project_config
+                    .tags
+                    .get_or_insert(vec![])
+                    .push(create_id(tag)?);
+            }
+        }
+
+        if !tasks.is_empty() {
+            project_config
+                .tasks
+                .get_or_insert(BTreeMap::default())
+                .extend(tasks);
+        }
+
+        Ok(())
+    }
+
+    fn get_task_id(&self, operation_name: &str) -> AnyResult<Id> {
+        match self.operations.get(operation_name) {
+            Some(operation) => Ok(operation.task_id.clone()),
+            None => create_id(
+                operation_name
+                    .strip_prefix("_phase:")
+                    .unwrap_or(operation_name),
+            ),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::turbo_migrator::TurboMigrator;
 use extism_pdk::*;
+use moon_extension_common::migrator::{run_migration, TaskConflictStrategy};
+use moon_extension_common::project_graph::ProjectGraph;
 use moon_pdk::*;
-use starbase_utils::{fs, glob, json};
+use starbase_utils::{glob, json};
 
 #[host_fn]
 extern "ExtismHost" {
@@ -24,47 +26,81 @@ pub fn register_extension(
 pub struct MigrateTurborepoExtensionArgs {
     #[arg(long)]
     pub bun: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
 }
 
 #[plugin_fn]
 pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
     let args = parse_args::<MigrateTurborepoExtensionArgs>(&input.args)?;
     let workspace_root = &input.context.workspace_root;
-    let mut migrator = TurboMigrator::new(&input.context, args.bun)?;
 
-    // Migrate the workspace root config first
-    let root_config_path = workspace_root.join("turbo.json");
+    run_migration(
+        workspace_root,
+        "Turborepo",
+        args.dry_run,
+        args.restore,
+        || {
+            let mut migrator = TurboMigrator::new(&input.context, args.bun)?;
+
+            // Migrate the workspace root config first
+            let root_config_path = workspace_root.join("turbo.json");
+
+            if root_config_path.exists() {
+                host_log!(stdout, "Migrating root config <file>turbo.json</file>",);
+
+                migrator.migrate_root_config(json::read_file(&root_config_path)?)?;
 
-    if root_config_path.exists() {
-        host_log!(stdout, "Migrating root config <file>turbo.json</file>",);
+                migrator.inner.remove_file(root_config_path);
+            }
 
-        migrator.migrate_root_config(json::read_file(&root_config_path)?)?;
+            // Then migrate project configs
+            for project_config_path in
+                glob::walk_files(workspace_root, ["**/*/turbo.json", "!**/node_modules/**/*"])?
+            {
+                let rel_config_path = project_config_path.strip_prefix(workspace_root).unwrap();
+                let project_source = rel_config_path.parent().unwrap().to_string_lossy();
 
-        fs::remove(root_config_path)?;
-    }
+                host_log!(
+                    stdout,
+                    "Migrating project config <file>{}</file>",
+                    rel_config_path.display()
+                );
 
-    // Then migrate project configs
-    for project_config_path in
-        glob::walk_files(workspace_root, ["**/*/turbo.json", "!**/node_modules/**/*"])?
-    {
-        let rel_config_path = project_config_path.strip_prefix(workspace_root).unwrap();
-        let project_source = rel_config_path.parent().unwrap().to_string_lossy();
+                migrator.migrate_project_config(
+                    &project_source,
+                    json::read_file(&project_config_path)?,
+                )?;
 
-        host_log!(
-            stdout,
-            "Migrating project config <file>{}</file>",
-            rel_config_path.display()
-        );
+                migrator
+                    .inner
+                    .remove_file(workspace_root.join(rel_config_path));
+            }
 
-        migrator.migrate_project_config(&project_source, json::read_file(&project_config_path)?)?;
+            // Resolve migrated tasks that collide with existing moon tasks
+            migrator.inner.resolve_task_conflicts(args.on_conflict)?;
 
-        fs::remove(project_config_path)?;
-    }
+            // Ensure all task targets and dependencies point to known projects
+            let mut project_graph = ProjectGraph::load_with_migrated(&migrator.inner)?;
 
-    // Write the new config files
-    migrator.inner.save_configs()?;
+            for package in &migrator.packages.packages {
+                project_graph.add_project(
+                    &package.id,
+                    &package.source,
+                    Some(package.alias.clone()),
+                );
+            }
 
-    host_log!(stdout, "Successfully migrated from Turborepo to moon!");
+            project_graph.validate_references(&migrator.inner)?;
 
-    Ok(())
+            Ok((migrator.inner, migrator.report))
+        },
+    )
 }
@@ -3,7 +3,7 @@
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum TurboEnvMode {
     Loose,
@@ -11,7 +11,7 @@ pub enum TurboEnvMode {
     Strict,
 }
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum TurboOutputMode {
     #[default]
@@ -30,7 +30,7 @@ pub enum TurboUi {
     Tui,
 }
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TurboTask {
     pub cache: Option<bool>,
@@ -66,8 +66,3 @@ pub struct TurboJson {
     pub global_dot_env: Option<Vec<String>>,
     pub pipeline: Option<FxHashMap<String, TurboTask>>,
 }
-
-#[derive(Default, Deserialize)]
-pub struct PackageJson {
-    pub name: Option<String>,
-}
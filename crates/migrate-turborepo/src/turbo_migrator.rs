@@ -1,30 +1,27 @@
 use crate::turbo_json::*;
-use moon_common::Id;
 use moon_config::{
     FilePath, InputPath, OutputPath, PartialTaskArgs, PartialTaskConfig, PartialTaskDependency,
     PartialTaskOptionsConfig, PartialWorkspaceProjects, PlatformType, PortablePath,
-    TaskOptionEnvFile, TaskOutputStyle,
+    TaskMergeStrategy, TaskOptionEnvFile, TaskOutputStyle,
 };
 use moon_extension_common::migrator::*;
+use moon_extension_common::report::*;
+use moon_extension_common::workspace_packages::*;
 use moon_pdk::*;
 use moon_target::Target;
-use rustc_hash::FxHashSet;
-use starbase_utils::glob;
-use starbase_utils::{fs, json};
+use rustc_hash::FxHashMap;
 use std::collections::BTreeMap;
 use std::str::FromStr;
 
-pub struct PackageEntry {
-    id: Id,
-    alias: String,
-    source: String,
-}
-
 pub struct TurboMigrator {
     pub inner: Migrator,
     pub package_manager: String,
-    pub package_globs: FxHashSet<String>,
-    pub packages: Vec<PackageEntry>,
+    pub packages: WorkspacePackages,
+    pub report: MigrationReport,
+    /// Whether only listed environment variables were available to tasks.
+    pub env_mode: TurboEnvMode,
+    /// Global tasks of the root `turbo.json`, that package configs can extend.
+    pub root_tasks: FxHashMap<String, TurboTask>,
 }
 
 impl TurboMigrator {
@@ -35,62 +32,80 @@ impl TurboMigrator {
             migrator.platform = PlatformType::Bun;
         }
 
-        // Load current packages
-        let mut packages = vec![];
-        let mut package_globs = FxHashSet::default();
-
-        for package_json_path in glob::walk_files(
-            &context.workspace_root,
-            ["**/*/package.json", "!**/node_modules/**/*"],
-        )? {
-            let package_json: PackageJson = json::read_file(&package_json_path)?;
-
-            if let Some(name) = package_json.name {
-                let package_root = package_json_path.parent().unwrap();
-                let package_source = package_root.strip_prefix(&context.workspace_root).unwrap();
-
-                package_globs.insert(format!(
-                    "{}/*",
-                    package_source.parent().unwrap().to_string_lossy()
-                ));
-
-                packages.push(PackageEntry {
-                    id: create_id(fs::file_name(package_root))?,
-                    alias: name,
-                    source: package_source.to_string_lossy().to_string(),
-                });
-            }
-        }
-
         Ok(Self {
             package_manager: migrator.detect_package_manager(),
+            packages: WorkspacePackages::load(&context.workspace_root)?,
             inner: migrator,
-            package_globs,
-            packages,
+            report: MigrationReport::default(),
+            env_mode: TurboEnvMode::default(),
+            root_tasks: FxHashMap::default(),
         })
     }
 
-    fn find_project_task_from_script(&self, script: &str) -> AnyResult<(&PackageEntry, String)> {
-        let mut parts = script.split('#');
-        let package_name = parts.next().unwrap();
-        let task_id = parts.next().unwrap();
-        let project = self.find_project_in_packages(package_name)?;
+    pub fn migrate_root_config(&mut self, mut turbo_json: TurboJson) -> AnyResult<()> {
+        let file = "turbo.json";
+        let mut implicit_inputs = vec![];
 
-        Ok((project, task_id.to_owned()))
-    }
+        // Strict mode is the default since v2, which replaced `pipeline` with `tasks`
+        self.env_mode = turbo_json
+            .env_mode
+            .unwrap_or(if turbo_json.tasks.is_some() {
+                TurboEnvMode::Strict
+            } else {
+                TurboEnvMode::Loose
+            });
 
-    fn find_project_in_packages(&self, package_name: &str) -> AnyResult<&PackageEntry> {
-        for package in &self.packages {
-            if package.id == package_name || package.alias == package_name {
-                return Ok(package);
+        if self.env_mode == TurboEnvMode::Strict {
+            if turbo_json.env_mode.is_some() {
+                self.report.approximated(
+                    file,
+                    "/envMode",
+                    "Strict mode is not enforced, moon does not filter environment variables and behaves like loose mode. Use the task env setting to set variables explicitly",
+                );
+            } else {
+                self.report.approximated(
+                    file,
+                    "/tasks",
+                    "Strict mode is implicit since v2, but is not enforced, moon does not filter environment variables and behaves like loose mode. Use the task env setting to set variables explicitly",
+                );
             }
         }
 
-        Err(anyhow!("Unable to migrate task as package <id>{package_name}</id> does not exist. Is it within the workspace?"))
-    }
+        if turbo_json.global_pass_through_env.is_some() {
+            self.migrate_pass_through_env(file, "/globalPassThroughEnv");
+        }
 
-    pub fn migrate_root_config(&mut self, mut turbo_json: TurboJson) -> AnyResult<()> {
-        let mut implicit_inputs = vec![];
+        if turbo_json.cache_dir.is_some() {
+            self.report.unsupported(
+                file,
+                "/cacheDir",
+                "Cache directory is not configurable, moon caches to .moon/cache",
+            );
+        }
+
+        if turbo_json.daemon.is_some() {
+            self.report
+                .unsupported(file, "/daemon", "Daemon is not supported");
+        }
+
+        if turbo_json.ui.is_some() {
+            self.report.unsupported(
+                file,
+                "/ui",
+                "Terminal UI is not supported, task output is streamed",
+            );
+        }
+
+        if turbo_json
+            .dangerously_disable_package_manager_check
+            .is_some()
+        {
+            self.report.unsupported(
+                file,
+                "/dangerouslyDisablePackageManagerCheck",
+                "Package manager check is not supported",
+            );
+        }
 
         if let Some(global_deps) = turbo_json.global_dependencies.take() {
             for dep in global_deps {
@@ -105,8 +120,14 @@ impl TurboMigrator {
         }
 
         if let Some(global_env) = turbo_json.global_env.take() {
-            for env in global_env {
-                implicit_inputs.push(InputPath::EnvVar(env.to_owned()));
+            for (index, env) in global_env.iter().enumerate() {
+                if let Some(input) = self.migrate_env_var(
+                    env,
+                    file,
+                    &create_pointer(["globalEnv", &index.to_string()]),
+                )? {
+                    implicit_inputs.push(input);
+                }
             }
         }
 
@@ -118,17 +139,17 @@ impl TurboMigrator {
                 .extend(implicit_inputs);
         }
 
-        if !self.package_globs.is_empty() {
+        if !self.packages.globs.is_empty() {
             let workspace_config = self.inner.load_workspace_config()?;
 
             if workspace_config.projects.is_none() {
                 workspace_config.projects = Some(PartialWorkspaceProjects::Globs(
-                    self.package_globs.clone().into_iter().collect(),
+                    self.packages.globs.clone().into_iter().collect(),
                 ));
             }
         }
 
-        self.migrate_pipeline(turbo_json, None)?;
+        self.migrate_pipeline(turbo_json, None, false)?;
 
         Ok(())
     }
@@ -138,22 +159,53 @@ impl TurboMigrator {
         project_source: &str,
         turbo_json: TurboJson,
     ) -> AnyResult<()> {
-        self.migrate_pipeline(turbo_json, Some(project_source))
+        let mut extends_root = false;
+
+        // Root tasks are inherited from .moon/tasks by all projects
+        if let Some(extends) = &turbo_json.extends {
+            for (index, extend) in extends.iter().enumerate() {
+                if extend == "//" {
+                    extends_root = true;
+                } else {
+                    self.report.unsupported(
+                        format!("{project_source}/turbo.json"),
+                        create_pointer(["extends", &index.to_string()]),
+                        "Only extending the root configuration (//) is supported",
+                    );
+                }
+            }
+        }
+
+        self.migrate_pipeline(turbo_json, Some(project_source), extends_root)
     }
 
     pub fn migrate_pipeline(
         &mut self,
         turbo_json: TurboJson,
         from_source: Option<&str>,
+        extends_root: bool,
     ) -> AnyResult<()> {
+        let file = match from_source {
+            Some(source) => format!("{source}/turbo.json"),
+            None => "turbo.json".into(),
+        };
+        let key = if turbo_json.tasks.is_some() {
+            "tasks"
+        } else {
+            "pipeline"
+        };
+
         let Some(pipeline) = turbo_json.tasks.or(turbo_json.pipeline) else {
             return Ok(());
         };
 
         // package.json script names to turbo tasks
         for (script, task) in pipeline {
+            let pointer = create_pointer([key, &script]);
+
             let project_source;
             let script_name;
+            let mut root_task = None;
 
             // Root-level task
             if let Some(root_script) = script.strip_prefix("//#") {
@@ -163,17 +215,24 @@ impl TurboMigrator {
             // Project-scoped task
             else if script.contains('#') {
                 (project_source, script_name) = self
-                    .find_project_task_from_script(&script)
+                    .packages
+                    .find_package_script(&script)
                     .map(|(p, i)| (p.source.to_owned(), i))?;
             }
             // For a source task
             else if let Some(source) = from_source {
+                if extends_root {
+                    root_task = self.root_tasks.get(&script).cloned();
+                }
+
                 project_source = source.to_owned();
                 script_name = script;
             }
             // Global task
             else {
-                let task = self.migrate_task(task, &script)?;
+                self.root_tasks.insert(script.clone(), task.clone());
+
+                let task = self.migrate_task(task, &script, &file, &pointer)?;
                 let task_id = create_id(&script)?;
 
                 self.inner
@@ -185,7 +244,18 @@ impl TurboMigrator {
                 continue;
             }
 
-            let task = self.migrate_task(task, &script_name)?;
+            let task = match root_task {
+                Some(root_task) => {
+                    self.migrate_extended_task(root_task, task, &script_name, &file, &pointer)?
+                }
+                None => self.migrate_task(task, &script_name, &file, &pointer)?,
+            };
+
+            // Nothing was overridden, so the inherited task is used as-is
+            if task == PartialTaskConfig::default() {
+                continue;
+            }
+
             let task_id = create_id(&script_name)?;
 
             self.inner
@@ -198,10 +268,42 @@ impl TurboMigrator {
         Ok(())
     }
 
+    /// Migrate a package task that overrides a root task field-by-field,
+    /// so that the moon task only contains the fields that differ
+    /// from the inherited task.
+    pub fn migrate_extended_task(
+        &mut self,
+        root_task: TurboTask,
+        turbo_task: TurboTask,
+        package_script: &str,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<PartialTaskConfig> {
+        // Only report on fields that were overridden by the package
+        self.report_task(&turbo_task, file, pointer);
+
+        let inherited_config = self.convert_task(&root_task, package_script)?;
+        let merged_config =
+            self.convert_task(&merge_tasks(&root_task, turbo_task), package_script)?;
+
+        Ok(diff_tasks(inherited_config, merged_config))
+    }
+
     pub fn migrate_task(
-        &self,
+        &mut self,
         turbo_task: TurboTask,
         package_script: &str,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<PartialTaskConfig> {
+        self.report_task(&turbo_task, file, pointer);
+        self.convert_task(&turbo_task, package_script)
+    }
+
+    fn convert_task(
+        &self,
+        turbo_task: &TurboTask,
+        package_script: &str,
     ) -> AnyResult<PartialTaskConfig> {
         let mut config = PartialTaskConfig::default();
         let mut inputs = vec![];
@@ -216,34 +318,14 @@ impl TurboMigrator {
             let mut deps: Vec<Target> = vec![];
 
             for dep in depends_on {
-                // $ENV input
-                if let Some(env) = dep.strip_prefix('$') {
-                    inputs.push(InputPath::EnvVar(env.into()));
-                    continue;
-                }
-
-                // ^:task
-                if let Some(dep) = dep.strip_prefix('^') {
-                    deps.push(
-                        Target::parse(format!("^:{dep}").as_str()).map_err(map_miette_error)?,
-                    );
-                    continue;
-                }
-
-                // project:task
-                if dep.contains('#') {
-                    let (package, script) = self.find_project_task_from_script(dep)?;
-                    let task_id = create_id(&script)?;
-
-                    deps.push(
-                        Target::parse(format!("{}:{task_id}", package.id).as_str())
-                            .map_err(map_miette_error)?,
-                    );
-                    continue;
-                }
-
-                // ~:task
-                deps.push(Target::parse(dep).map_err(map_miette_error)?);
+                match self.packages.parse_dependency(dep)? {
+                    PipelineDependency::EnvVar(env) => {
+                        inputs.push(InputPath::EnvVar(env));
+                    }
+                    PipelineDependency::Target(target) => {
+                        deps.push(target);
+                    }
+                };
             }
 
             if !deps.is_empty() {
@@ -258,17 +340,15 @@ impl TurboMigrator {
         // Inputs
         if let Some(env_vars) = &turbo_task.env {
             for env in env_vars {
-                inputs.push(InputPath::EnvVar(env.into()));
+                if let Some(input) = convert_env_var(env)? {
+                    inputs.push(input);
+                }
             }
         }
 
         if let Some(raw_inputs) = &turbo_task.inputs {
             for input in raw_inputs {
-                if input == "$TURBO_DEFAULT$" {
-                    continue;
-                }
-
-                inputs.push(InputPath::from_str(input)?);
+                inputs.push(migrate_input(input)?);
             }
         }
 
@@ -312,20 +392,22 @@ impl TurboMigrator {
             });
         }
 
-        if let Some(output_mode) = turbo_task.output_logs.or(turbo_task.output_mode) {
-            let output_style = match output_mode {
-                TurboOutputMode::HashOnly => Some(TaskOutputStyle::Hash),
-                TurboOutputMode::NewOnly => Some(TaskOutputStyle::Buffer),
-                TurboOutputMode::ErrorsOnly => Some(TaskOutputStyle::BufferOnlyFailure),
-                _ => None,
-            };
+        let output_style = match turbo_task
+            .output_logs
+            .as_ref()
+            .or(turbo_task.output_mode.as_ref())
+        {
+            Some(TurboOutputMode::HashOnly) => Some(TaskOutputStyle::Hash),
+            Some(TurboOutputMode::NewOnly) => Some(TaskOutputStyle::Buffer),
+            Some(TurboOutputMode::ErrorsOnly) => Some(TaskOutputStyle::BufferOnlyFailure),
+            Some(TurboOutputMode::None | TurboOutputMode::Full) | None => None,
+        };
 
-            if output_style.is_some() {
-                config
-                    .options
-                    .get_or_insert(PartialTaskOptionsConfig::default())
-                    .output_style = output_style;
-            }
+        if output_style.is_some() {
+            config
+                .options
+                .get_or_insert(PartialTaskOptionsConfig::default())
+                .output_style = output_style;
         }
 
         if turbo_task.interactive == Some(true) {
@@ -341,4 +423,243 @@ impl TurboMigrator {
 
         Ok(config)
     }
+
+    /// Report the settings of a task that can't be converted exactly.
+    fn report_task(&mut self, turbo_task: &TurboTask, file: &str, pointer: &str) {
+        if let Some(env_vars) = &turbo_task.env {
+            for (index, env) in env_vars.iter().enumerate() {
+                self.report_env_var(
+                    env,
+                    file,
+                    &format!("{pointer}{}", create_pointer(["env", &index.to_string()])),
+                );
+            }
+        }
+
+        if turbo_task.pass_through_env.is_some() {
+            self.migrate_pass_through_env(file, &format!("{pointer}/passThroughEnv"));
+        }
+
+        if turbo_task.dot_env.as_ref().is_some_and(|dot_env| {
+            dot_env
+                .iter()
+                .filter(|env_file| *env_file != TURBO_EXTENDS)
+                .count()
+                != 1
+        }) {
+            self.report.approximated(
+                file,
+                format!("{pointer}/dotEnv"),
+                "Multiple env files are not supported, only .env is loaded",
+            );
+        }
+
+        let output_key = if turbo_task.output_logs.is_some() {
+            "outputLogs"
+        } else {
+            "outputMode"
+        };
+
+        match turbo_task
+            .output_logs
+            .as_ref()
+            .or(turbo_task.output_mode.as_ref())
+        {
+            Some(TurboOutputMode::NewOnly) => {
+                self.report.approximated(
+                    file,
+                    format!("{pointer}/{output_key}"),
+                    "Output is buffered and always displayed, not only for cache misses",
+                );
+            }
+            Some(TurboOutputMode::None) => {
+                self.report.unsupported(
+                    file,
+                    format!("{pointer}/{output_key}"),
+                    "Hiding all output is not supported",
+                );
+            }
+            _ => {}
+        };
+
+        if turbo_task.persistent == Some(true) {
+            self.report.approximated(
+                file,
+                format!("{pointer}/persistent"),
+                "Persistent tasks were marked as local, which also disables caching and CI",
+            );
+        }
+    }
+
+    /// Hashed variables become env inputs, where wildcards are supported
+    /// but exclusions are not.
+    fn migrate_env_var(
+        &mut self,
+        env: &str,
+        file: &str,
+        pointer: &str,
+    ) -> AnyResult<Option<InputPath>> {
+        self.report_env_var(env, file, pointer);
+
+        convert_env_var(env)
+    }
+
+    fn report_env_var(&mut self, env: &str, file: &str, pointer: &str) {
+        if env.starts_with('!') {
+            self.report.unsupported(
+                file,
+                pointer,
+                "Excluding environment variables from the hash is not supported",
+            );
+        }
+    }
+
+    /// Tasks always inherit the entire environment in moon, and pass-through
+    /// variables never affected the hash, so only strict mode differs.
+    fn migrate_pass_through_env(&mut self, file: &str, pointer: &str) {
+        if self.env_mode == TurboEnvMode::Strict {
+            self.report.approximated(
+                file,
+                pointer,
+                "Environment variables are not filtered, so all variables are passed through to tasks, which is the same as loose mode",
+            );
+        }
+    }
+}
+
+fn convert_env_var(env: &str) -> AnyResult<Option<InputPath>> {
+    if env.starts_with('!') {
+        return Ok(None);
+    }
+
+    Ok(Some(InputPath::from_str(&format!("${env}"))?))
+}
+
+// https://turbo.build/repo/docs/reference/configuration#inputs
+fn migrate_input(input: &str) -> AnyResult<InputPath> {
+    // All files in the package that are not gitignored, which matches
+    // the default sources that moon hashes when no inputs are configured
+    if input == "$TURBO_DEFAULT$" {
+        return Ok(InputPath::ProjectGlob("**/*".into()));
+    }
+
+    let (negated, input) = match input.strip_prefix('!') {
+        Some(input) => (true, input),
+        None => (false, input),
+    };
+
+    let input = match input.strip_prefix("$TURBO_ROOT$/") {
+        Some(workspace_path) => format!("/{workspace_path}"),
+        None => input.to_owned(),
+    };
+
+    if negated {
+        // Negations are always globs, and are applied regardless of order
+        return Ok(match input.strip_prefix('/') {
+            Some(workspace_path) => InputPath::WorkspaceGlob(format!("!{workspace_path}")),
+            None => InputPath::ProjectGlob(format!("!{input}")),
+        });
+    }
+
+    Ok(InputPath::from_str(&input)?)
+}
+
+// https://turbo.build/repo/docs/reference/package-configurations
+const TURBO_EXTENDS: &str = "$TURBO_EXTENDS$";
+
+fn merge_lists(base: &Option<Vec<String>>, list: Option<Vec<String>>) -> Option<Vec<String>> {
+    match list {
+        Some(list) if list.iter().any(|item| item == TURBO_EXTENDS) => {
+            let mut merged = base.clone().unwrap_or_default();
+            merged.extend(list.into_iter().filter(|item| item != TURBO_EXTENDS));
+
+            Some(merged)
+        }
+        Some(list) => Some(list),
+        None => base.clone(),
+    }
+}
+
+fn merge_tasks(base: &TurboTask, task: TurboTask) -> TurboTask {
+    TurboTask {
+        cache: task.cache.or(base.cache),
+        depends_on: merge_lists(&base.depends_on, task.depends_on),
+        env: merge_lists(&base.env, task.env),
+        inputs: merge_lists(&base.inputs, task.inputs),
+        outputs: merge_lists(&base.outputs, task.outputs),
+        pass_through_env: merge_lists(&base.pass_through_env, task.pass_through_env),
+        persistent: task.persistent.or(base.persistent),
+        interactive: task.interactive.or(base.interactive),
+        output_logs: task.output_logs.or(base.output_logs.clone()),
+        dot_env: merge_lists(&base.dot_env, task.dot_env),
+        output_mode: task.output_mode.or(base.output_mode.clone()),
+    }
+}
+
+fn diff_lists<T: Clone + PartialEq>(
+    inherited: Option<Vec<T>>,
+    merged: Option<Vec<T>>,
+) -> (Option<Vec<T>>, Option<TaskMergeStrategy>) {
+    let inherited = inherited.unwrap_or_default();
+    let merged = merged.unwrap_or_default();
+
+    if merged == inherited {
+        (None, None)
+    }
+    // Tasks are appended to the inherited task by default
+    else if merged.starts_with(&inherited) {
+        (Some(merged[inherited.len()..].to_vec()), None)
+    } else {
+        (Some(merged), Some(TaskMergeStrategy::Replace))
+    }
+}
+
+fn diff_tasks(inherited: PartialTaskConfig, merged: PartialTaskConfig) -> PartialTaskConfig {
+    let mut config = PartialTaskConfig::default();
+    let mut options = PartialTaskOptionsConfig::default();
+
+    if merged.command != inherited.command {
+        config.command = merged.command;
+    }
+
+    if merged.description != inherited.description {
+        config.description = merged.description;
+    }
+
+    (config.deps, options.merge_deps) = diff_lists(inherited.deps, merged.deps);
+    (config.inputs, options.merge_inputs) = diff_lists(inherited.inputs, merged.inputs);
+    (config.outputs, options.merge_outputs) = diff_lists(inherited.outputs, merged.outputs);
+
+    if merged.local != inherited.local {
+        config.local = Some(merged.local.unwrap_or_default());
+    }
+
+    let inherited_options = inherited.options.unwrap_or_default();
+    let merged_options = merged.options.unwrap_or_default();
+
+    if merged_options.cache != inherited_options.cache {
+        options.cache = Some(merged_options.cache.unwrap_or(true));
+    }
+
+    if merged_options.env_file != inherited_options.env_file {
+        options.env_file = Some(
+            merged_options
+                .env_file
+                .unwrap_or(TaskOptionEnvFile::Enabled(false)),
+        );
+    }
+
+    if merged_options.interactive != inherited_options.interactive {
+        options.interactive = Some(merged_options.interactive.unwrap_or_default());
+    }
+
+    if merged_options.output_style != inherited_options.output_style {
+        options.output_style = merged_options.output_style;
+    }
+
+    if options != PartialTaskOptionsConfig::default() {
+        config.options = Some(options);
+    }
+
+    config
 }
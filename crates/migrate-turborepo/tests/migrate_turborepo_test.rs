@@ -72,6 +72,27 @@ mod migrate_turborepo {
         assert_snapshot!(fs::read_to_string(sandbox.path().join("server/moon.yml")).unwrap());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn only_overrides_fields_when_extending_root() {
+        let sandbox = create_sandbox("extends");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("apps/web/turbo.json").exists());
+        assert!(!sandbox.path().join("apps/docs/turbo.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/web/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/docs/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn converts_to_a_root_project() {
         let sandbox = create_sandbox("root-project");
@@ -106,6 +127,21 @@ mod migrate_turborepo {
         assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn preserves_comments_when_merging_existing_tasks() {
+        let sandbox = create_sandbox("root-merge-comments");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn supports_no_pipeline() {
         let sandbox = create_sandbox("missing-pipeline");
@@ -122,6 +158,234 @@ mod migrate_turborepo {
         assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_files_in_dry_run() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("turbo.json").exists());
+        assert!(sandbox.path().join("client/turbo.json").exists());
+        assert!(sandbox.path().join("server/turbo.json").exists());
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join("client/moon.yml").exists());
+        assert!(!sandbox.path().join("server/moon.yml").exists());
+    }
+
+    // The host prints logs directly to stdout, so run the dry run in a child
+    // process of this test binary to capture them.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn run_dry_run_in_sandbox() {
+        let Ok(root) = std::env::var("DRY_RUN_SANDBOX") else {
+            return;
+        };
+        let plugin = create_extension("test", std::path::Path::new(&root));
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(std::path::Path::new(&root)),
+            })
+            .await;
+    }
+
+    #[test]
+    fn prints_a_diff_in_dry_run() {
+        let sandbox = create_sandbox("monorepo");
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "migrate_turborepo::run_dry_run_in_sandbox",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env("DRY_RUN_SANDBOX", sandbox.path())
+            .env("NO_COLOR", "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains("--- /dev/null\n+++ .moon/tasks/node.yml"));
+        assert!(stdout.contains("--- /dev/null\n+++ client/moon.yml"));
+        assert!(stdout.contains("+tasks:"));
+        assert!(stdout.contains("Would remove turbo.json"));
+        assert!(stdout.contains("Would remove client/turbo.json"));
+        assert!(stdout.contains("Dry run complete, no files were changed!"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_a_report_of_unsupported_settings() {
+        let sandbox = create_sandbox("root-only");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn expands_default_and_negated_inputs() {
+        let sandbox = create_sandbox("inputs");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn combines_default_root_and_negated_inputs() {
+        let sandbox = create_sandbox("inputs-combined");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/web/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/docs/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_env_vars_and_reports_strict_mode() {
+        let sandbox = create_sandbox("env-modes");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/tasks/node.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_a_report_if_everything_migrated() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn backs_up_original_files() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        let backup_dir = fs::read_dir(sandbox.path().join(".moon/migrate-backup"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+
+        assert!(backup_dir.join("backup.json").exists());
+        assert!(backup_dir.join("turbo.json").exists());
+        assert!(backup_dir.join("client/turbo.json").exists());
+        assert!(backup_dir.join("server/turbo.json").exists());
+        assert!(backup_dir.join(".moon/workspace.yml").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_restore_from_backup() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+        let workspace_config =
+            fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap();
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("turbo.json").exists());
+        assert!(sandbox.path().join("client/turbo.json").exists());
+        assert!(sandbox.path().join("server/turbo.json").exists());
+        assert!(!sandbox.path().join(".moon/tasks/node.yml").exists());
+        assert!(!sandbox.path().join("client/moon.yml").exists());
+        assert!(!sandbox.path().join("server/moon.yml").exists());
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap(),
+            workspace_config
+        );
+        assert_eq!(
+            fs::read_dir(sandbox.path().join(".moon/migrate-backup"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "No migration backup found to restore.")]
+    async fn errors_if_no_backup_to_restore() {
+        let sandbox = create_sandbox("monorepo");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[should_panic(expected = "Unable to migrate task as package client does not exist.")]
     async fn errors_if_a_task_points_to_an_unknown_project() {
@@ -149,4 +413,95 @@ mod migrate_turborepo {
             })
             .await;
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "an interactive task cannot be persistent")]
+    async fn errors_if_generated_config_fails_schema_validation() {
+        let sandbox = create_sandbox("error-invalid-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--on-conflict".into(), "merge".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(expected = "an interactive task cannot be persistent")]
+    async fn errors_if_generated_config_fails_schema_validation_in_dry_run() {
+        let sandbox = create_sandbox("error-invalid-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into(), "--on-conflict".into(), "merge".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+
+    mod task_conflicts {
+        use super::*;
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn overwrites_existing_tasks_by_default() {
+            let sandbox = create_sandbox("task-conflicts");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec![],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("client/moon.yml")).unwrap());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn skips_existing_tasks() {
+            let sandbox = create_sandbox("task-conflicts");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec!["--on-conflict".into(), "skip".into()],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("client/moon.yml")).unwrap());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn merges_existing_tasks() {
+            let sandbox = create_sandbox("task-conflicts");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec!["--on-conflict".into(), "merge".into()],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+
+            assert_snapshot!(fs::read_to_string(sandbox.path().join("client/moon.yml")).unwrap());
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        #[should_panic(expected = "client/moon.yml: Task build already exists.")]
+        async fn errors_for_existing_tasks() {
+            let sandbox = create_sandbox("task-conflicts");
+            let plugin = create_extension("test", sandbox.path());
+
+            plugin
+                .execute_extension(ExecuteExtensionInput {
+                    args: vec!["--on-conflict".into(), "error".into()],
+                    context: plugin.create_context(sandbox.path()),
+                })
+                .await;
+        }
+    }
 }
@@ -0,0 +1,6 @@
+#[cfg(feature = "wasm")]
+mod migrate_workspaces_ext;
+mod workspaces_migrator;
+
+#[cfg(feature = "wasm")]
+pub use migrate_workspaces_ext::*;
@@ -0,0 +1,114 @@
+use crate::workspaces_migrator::WorkspacesMigrator;
+use extism_pdk::*;
+use moon_extension_common::migrator::{run_migration, TaskConflictStrategy};
+use moon_extension_common::package_json::{create_package_json_globs, PackageJson};
+use moon_pdk::*;
+use starbase_utils::{glob, json, yaml};
+
+#[host_fn]
+extern "ExtismHost" {
+    fn host_log(input: Json<HostLogInput>);
+}
+
+#[plugin_fn]
+pub fn register_extension(
+    Json(_): Json<ExtensionMetadataInput>,
+) -> FnResult<Json<ExtensionMetadataOutput>> {
+    Ok(Json(ExtensionMetadataOutput {
+        name: "Migrate workspaces".into(),
+        description: Some("Migrate a pnpm, Yarn, npm, or Bun workspace to moon by converting package scripts into moon configuration files.".into()),
+        plugin_version: env!("CARGO_PKG_VERSION").into(),
+        config_schema: None,
+    }))
+}
+
+#[derive(Args)]
+pub struct MigrateWorkspacesExtensionArgs {
+    #[arg(long)]
+    pub bun: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: TaskConflictStrategy,
+
+    #[arg(long)]
+    pub restore: bool,
+}
+
+#[plugin_fn]
+pub fn execute_extension(Json(input): Json<ExecuteExtensionInput>) -> FnResult<()> {
+    let args = parse_args::<MigrateWorkspacesExtensionArgs>(&input.args)?;
+    let workspace_root = &input.context.workspace_root;
+
+    run_migration(
+        workspace_root,
+        "package manager workspaces",
+        args.dry_run,
+        args.restore,
+        || {
+            let mut migrator = WorkspacesMigrator::new(&input.context, args.bun)?;
+
+            // Find the workspace packages first
+            let root_package_path = workspace_root.join("package.json");
+            let root_package_json: PackageJson = if root_package_path.exists() {
+                json::read_file(&root_package_path)?
+            } else {
+                PackageJson::default()
+            };
+
+            let pnpm_workspace_path = workspace_root.join("pnpm-workspace.yaml");
+
+            if pnpm_workspace_path.exists() {
+                host_log!(
+                    stdout,
+                    "Migrating workspace packages from <file>pnpm-workspace.yaml</file>"
+                );
+
+                migrator.migrate_pnpm_workspace(
+                    yaml::read_file(&pnpm_workspace_path)?,
+                    &root_package_json,
+                )?;
+            } else if root_package_json.workspaces.is_some() {
+                host_log!(
+                    stdout,
+                    "Migrating workspace packages from <file>package.json</file>"
+                );
+
+                migrator.migrate_root_package(&root_package_json)?;
+            } else {
+                return Err(plugin_err!(
+            "Unable to migrate, no <file>pnpm-workspace.yaml</file> or <property>workspaces</property> in <file>package.json</file> found in the workspace root."
+        ));
+            }
+
+            // Then convert the scripts of each package
+            for package_json_path in glob::walk_files(
+                workspace_root,
+                &create_package_json_globs(&migrator.package_globs),
+            )? {
+                let rel_package_path = package_json_path.strip_prefix(workspace_root).unwrap();
+                let project_source = rel_package_path.parent().unwrap().to_string_lossy();
+
+                // The root package is not a project
+                if project_source.is_empty() {
+                    continue;
+                }
+
+                host_log!(
+                    stdout,
+                    "Migrating package scripts <file>{}</file>",
+                    rel_package_path.display()
+                );
+
+                migrator.migrate_package(&project_source, json::read_file(&package_json_path)?)?;
+            }
+
+            // Resolve migrated tasks that collide with existing moon tasks
+            migrator.inner.resolve_task_conflicts(args.on_conflict)?;
+
+            Ok((migrator.inner, migrator.report))
+        },
+    )
+}
@@ -0,0 +1,180 @@
+use moon_config::{
+    PartialTaskArgs, PartialTaskConfig, PartialTaskDependency, PartialWorkspaceProjects,
+    PlatformType,
+};
+use moon_extension_common::migrator::*;
+use moon_extension_common::package_json::*;
+use moon_extension_common::report::*;
+use moon_pdk::{map_miette_error, AnyResult, MoonContext};
+use moon_target::Target;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// https://pnpm.io/pnpm-workspace_yaml
+#[derive(Default, Deserialize)]
+pub struct PnpmWorkspaceYaml {
+    pub packages: Option<Vec<String>>,
+}
+
+pub struct WorkspacesMigrator {
+    pub inner: Migrator,
+    pub package_globs: Vec<String>,
+    pub package_manager: String,
+    pub report: MigrationReport,
+}
+
+impl WorkspacesMigrator {
+    pub fn new(context: &MoonContext, bun: bool) -> AnyResult<Self> {
+        let mut migrator = Migrator::new(&context.workspace_root)?;
+
+        if bun {
+            migrator.platform = PlatformType::Bun;
+        }
+
+        Ok(Self {
+            package_globs: vec![],
+            package_manager: migrator.detect_package_manager(),
+            inner: migrator,
+            report: MigrationReport::default(),
+        })
+    }
+
+    pub fn migrate_pnpm_workspace(
+        &mut self,
+        pnpm_workspace: PnpmWorkspaceYaml,
+        package_json: &PackageJson,
+    ) -> AnyResult<()> {
+        // The workspace file is only used by pnpm, even without a lockfile
+        if !matches!(self.inner.platform, PlatformType::Bun) {
+            self.package_manager = "pnpm".into();
+        }
+
+        self.migrate_package_globs(pnpm_workspace.packages.unwrap_or_default())?;
+        self.migrate_root_scripts(package_json);
+
+        Ok(())
+    }
+
+    pub fn migrate_root_package(&mut self, package_json: &PackageJson) -> AnyResult<()> {
+        if let Some(workspaces) = &package_json.workspaces {
+            self.migrate_package_globs(workspaces.get_globs().to_vec())?;
+        }
+
+        self.migrate_root_scripts(package_json);
+
+        Ok(())
+    }
+
+    pub fn migrate_root_scripts(&mut self, package_json: &PackageJson) {
+        if package_json
+            .scripts
+            .as_ref()
+            .is_some_and(|scripts| !scripts.is_empty())
+        {
+            self.report.unsupported(
+                "package.json",
+                "/scripts",
+                "Scripts of the root package are not converted, move them to a root-level project instead",
+            );
+        }
+    }
+
+    pub fn migrate_package(
+        &mut self,
+        project_source: &str,
+        package_json: PackageJson,
+    ) -> AnyResult<()> {
+        let Some(scripts) = package_json.scripts else {
+            return Ok(());
+        };
+
+        let file = format!("{project_source}/package.json");
+        let mut tasks = BTreeMap::default();
+
+        for name in scripts.keys() {
+            if LIFECYCLE_SCRIPTS.contains(&name.as_str()) {
+                continue;
+            }
+
+            let pre_hook = format!("pre{name}");
+            let post_hook = format!("post{name}");
+            let has_hooks = scripts.contains_key(&pre_hook) || scripts.contains_key(&post_hook);
+            let mut command = format!("{} run {name}", self.package_manager);
+            let mut deps = vec![];
+
+            // Hooks are converted into tasks, so don't let npm run them again
+            if has_hooks {
+                match self.package_manager.as_str() {
+                    "npm" => {
+                        command.push_str(" --ignore-scripts");
+                    }
+                    "bun" | "pnpm" | "yarn" => {
+                        self.report.approximated(
+                            &file,
+                            create_pointer(["scripts", name]),
+                            format!(
+                                "Hooks were converted to task dependencies, but {} may also run them automatically",
+                                self.package_manager
+                            ),
+                        );
+                    }
+                    _ => {}
+                };
+            }
+
+            // prebuild -> build
+            if scripts.contains_key(&pre_hook) {
+                deps.push(PartialTaskDependency::Target(
+                    Target::new_self(create_id(&pre_hook)?).map_err(map_miette_error)?,
+                ));
+            }
+
+            // build -> postbuild
+            if let Some(hooked_name) = name.strip_prefix("post") {
+                if scripts.contains_key(hooked_name) {
+                    deps.push(PartialTaskDependency::Target(
+                        Target::new_self(create_id(hooked_name)?).map_err(map_miette_error)?,
+                    ));
+
+                    self.report.approximated(
+                        &file,
+                        create_pointer(["scripts", name]),
+                        format!("Hook was converted to a task that depends on {hooked_name}, run it instead to include the hook"),
+                    );
+                }
+            }
+
+            tasks.insert(
+                create_id(name)?,
+                PartialTaskConfig {
+                    command: Some(PartialTaskArgs::String(command)),
+                    deps: if deps.is_empty() { None } else { Some(deps) },
+                    ..PartialTaskConfig::default()
+                },
+            );
+        }
+
+        if !tasks.is_empty() {
+            self.inner
+                .load_project_config(project_source)?
+                .tasks
+                .get_or_insert(BTreeMap::default())
+                .extend(tasks);
+        }
+
+        Ok(())
+    }
+
+    fn migrate_package_globs(&mut self, package_globs: Vec<String>) -> AnyResult<()> {
+        let workspace_config = self.inner.load_workspace_config()?;
+
+        if workspace_config.projects.is_none() {
+            workspace_config.projects =
+                Some(PartialWorkspaceProjects::Globs(package_globs.clone()));
+        }
+
+        self.package_globs = package_globs;
+
+        Ok(())
+    }
+}
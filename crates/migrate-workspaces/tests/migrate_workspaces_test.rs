@@ -0,0 +1,150 @@
+use moon_pdk_test_utils::{create_extension, ExecuteExtensionInput};
+use starbase_sandbox::{assert_snapshot, create_sandbox};
+use std::fs;
+
+mod migrate_workspaces {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_pnpm_workspace() {
+        let sandbox = create_sandbox("pnpm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("pnpm-workspace.yaml").exists());
+        assert!(sandbox.path().join("apps/web/moon.yml").exists());
+        assert!(sandbox.path().join("packages/ui/moon.yml").exists());
+        assert!(!sandbox.path().join("packages/internal/moon.yml").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/web/moon.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/ui/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_npm_workspaces() {
+        let sandbox = create_sandbox("npm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn converts_yarn_workspaces() {
+        let sandbox = create_sandbox("yarn");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join(".moon/workspace.yml")).unwrap());
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("packages/a/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_force_bun_instead_of_node() {
+        let sandbox = create_sandbox("pnpm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--bun".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("apps/web/moon.yml")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_a_report_of_unsupported_settings() {
+        let sandbox = create_sandbox("pnpm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(sandbox.path().join("migration-report.json").exists());
+
+        assert_snapshot!(fs::read_to_string(sandbox.path().join("migration-report.json")).unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn doesnt_write_files_in_dry_run() {
+        let sandbox = create_sandbox("pnpm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--dry-run".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+        assert!(!sandbox.path().join("apps/web/moon.yml").exists());
+        assert!(!sandbox.path().join("migration-report.json").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_restore_from_backup() {
+        let sandbox = create_sandbox("pnpm");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec!["--restore".into()],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+
+        assert!(!sandbox.path().join(".moon/workspace.yml").exists());
+        assert!(!sandbox.path().join("apps/web/moon.yml").exists());
+        assert!(!sandbox.path().join("packages/ui/moon.yml").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[should_panic(
+        expected = "Unable to migrate, no pnpm-workspace.yaml or workspaces in package.json found in the workspace root."
+    )]
+    async fn errors_if_no_workspaces() {
+        let sandbox = create_sandbox("missing-config");
+        let plugin = create_extension("test", sandbox.path());
+
+        plugin
+            .execute_extension(ExecuteExtensionInput {
+                args: vec![],
+                context: plugin.create_context(sandbox.path()),
+            })
+            .await;
+    }
+}